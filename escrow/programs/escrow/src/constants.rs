@@ -2,3 +2,14 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+// open offers a maker can have at once, bounds the registry account size
+#[constant]
+pub const MAX_OPEN_OFFERS: usize = 32;
+
+// distinct assets on each side of a bundle offer
+#[constant]
+pub const MAX_BUNDLE_ASSETS: usize = 5;
+
+// pyth solana receiver, owner of the `PriceUpdateV2` accounts oracle priced offers are quoted from
+pub const PYTH_RECEIVER_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
@@ -1,7 +1,58 @@
 use anchor_lang::prelude::*;
 
 #[error_code]
-pub enum ErrorCode {
-    #[msg("Custom error message")]
-    CustomError,
+pub enum EscrowErrors {
+    #[msg("Taken amount must be between 1 and the remaining offered amount")]
+    InvalidTakeAmount,
+
+    #[msg("Offer expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Offer has expired")]
+    OfferExpired,
+
+    #[msg("Offer hasn't expired yet")]
+    OfferNotExpired,
+
+    #[msg("Taker is not allowed to take this offer")]
+    TakerNotAllowed,
+
+    #[msg("Maker has too many open offers")]
+    TooManyOpenOffers,
+
+    #[msg("Bundle must hold 1 to 5 distinct assets on each side, with non zero amounts")]
+    InvalidBundle,
+
+    #[msg("Remaining accounts don't match the bundle assets")]
+    InvalidBundleAccounts,
+
+    #[msg("Bundle account doesn't match its expected address, mint or owner")]
+    InvalidBundleAccount,
+
+    #[msg("Oracle priced offers need a zero expected amount, a spread above -100% and non zero staleness and confidence bounds")]
+    InvalidPricing,
+
+    #[msg("Oracle priced offer needs its price feed")]
+    MissingPriceFeed,
+
+    #[msg("Price feed doesn't match the offer's feed")]
+    PriceFeedMismatch,
+
+    #[msg("Price feed is stale")]
+    StalePrice,
+
+    #[msg("Price feed returned an unusable price")]
+    InvalidPrice,
+
+    #[msg("Price feed isn't a supported oracle account")]
+    OracleNotSupported,
+
+    #[msg("Price is above the taker's max payment")]
+    PaymentAboveMax,
+
+    #[msg("Price update isn't fully verified")]
+    UnverifiedPrice,
+
+    #[msg("Price confidence interval is too wide")]
+    PriceUncertain,
 }
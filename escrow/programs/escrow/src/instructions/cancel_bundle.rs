@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowErrors,
+    state::{Bundle, BundleAsset, OfferRegistry},
+};
+
+#[derive(Accounts)]
+pub struct CancelBundle<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bundle", maker.key().as_ref(), bundle.id.to_le_bytes().as_ref()],
+        bump = bundle.bump,
+        close = maker,
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    #[account(
+        mut,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CancelBundle<'info> {
+    // remaining accounts per offered asset, in offered order: [mint, vault, maker_ata]
+    pub const ACCOUNTS_PER_ASSET: usize = 3;
+
+    pub fn withdraw_offered_assets(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let offered = self.bundle.offered.clone();
+        require!(
+            remaining_accounts.len() == offered.len() * Self::ACCOUNTS_PER_ASSET,
+            EscrowErrors::InvalidBundleAccounts
+        );
+
+        for (accounts, asset) in remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_ASSET)
+            .zip(offered)
+        {
+            self.withdraw_and_close_vault(accounts, asset)?;
+        }
+
+        // bundle itself is closed by its `close` constraint
+        self.registry.close_offer(self.bundle.id);
+
+        Ok(())
+    }
+
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        asset: BundleAsset,
+    ) -> Result<()> {
+        let [mint, vault, maker_ata] = accounts else {
+            return err!(EscrowErrors::InvalidBundleAccounts);
+        };
+        require_keys_eq!(mint.key(), asset.mint, EscrowErrors::InvalidBundleAccount);
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+
+        require_keys_eq!(
+            vault.key(),
+            get_associated_token_address_with_program_id(
+                &self.bundle.key(),
+                &asset.mint,
+                &self.token_program.key()
+            ),
+            EscrowErrors::InvalidBundleAccount
+        );
+        let maker_token = InterfaceAccount::<TokenAccount>::try_from(maker_ata)?;
+        require_keys_eq!(
+            maker_token.owner,
+            self.maker.key(),
+            EscrowErrors::InvalidBundleAccount
+        );
+        require_keys_eq!(
+            maker_token.mint,
+            asset.mint,
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        let id = self.bundle.id.to_le_bytes(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"bundle",
+            self.bundle.maker.as_ref(),
+            id.as_ref(),
+            &[self.bundle.bump],
+        ]];
+
+        // whole vault back to the maker, surplus sent to it on top of the asset included
+        let amount = InterfaceAccount::<TokenAccount>::try_from(vault)?.amount;
+
+        let transfer_accounts = TransferChecked {
+            from: vault.clone(),
+            mint: mint.clone(),
+            to: maker_ata.clone(),
+            authority: self.bundle.to_account_info(),
+        };
+
+        let transfer_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(transfer_context, amount, mint_account.decimals)?;
+
+        let close_accounts = CloseAccount {
+            account: vault.clone(),
+            destination: self.maker.to_account_info(),
+            authority: self.bundle.to_account_info(),
+        };
+
+        let close_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        );
+
+        close_account(close_context)?;
+
+        Ok(())
+    }
+}
@@ -5,10 +5,9 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::Escrow;
+use crate::{Escrow, OfferRegistry};
 
 #[derive(Accounts)]
-#[instruction(extra_seed: String)]
 pub struct CancelOffer<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
@@ -16,12 +15,19 @@ pub struct CancelOffer<'info> {
     #[account(
         mut,
         // has_one = maker, // i think you don't need it since escrow derivation is 1:1 related with Signer == maker
-        seeds = [b"escrow", maker.key().as_ref(), extra_seed.as_ref()],
+        seeds = [b"escrow", maker.key().as_ref(), escrow.id.to_le_bytes().as_ref()],
         bump = escrow.bump,
         close = maker,
     )]
     pub escrow: Account<'info, Escrow>,
 
+    #[account(
+        mut,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
     #[account(
         mut,
         // associated_token::mint = escrow.mint_a, //@audit :: any issues ?? since escrow.mint_a is pubkey && not the InterfaceAccount<'info, Mint>
@@ -52,21 +58,22 @@ pub struct CancelOffer<'info> {
 }
 
 impl<'info> CancelOffer<'info> {
-    pub fn withdraw_offered_amounts(&mut self, extra_seed: &String) -> Result<()> {
+    pub fn withdraw_offered_amounts(&mut self) -> Result<()> {
         let transfer_accounts = TransferChecked {
             from: self.vault.to_account_info(),
             // mint: self.escrow.mint_a.to_account_info(), //@issue :: cant call to_account_info on type PUbkey
             mint: self.mint_a.to_account_info(),
             to: self.maker_offered_ata.to_account_info(),
-            authority: self.maker.to_account_info(),
+            authority: self.escrow.to_account_info(), // vault is owned by the escrow, not the maker
         };
 
         // let signer_seeds: &[&[&[u8]]] = &[&[b"escrow", self.maker.key().as_ref(), extra_seed.as_ref(), &self.escrow.bump.to_le_bytes()]]; // self.maker.key()  --> error:: temporary value dropped while borrowing
 
+        let id = self.escrow.id.to_le_bytes(); // binding to prevent temporary value dropped errors
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"escrow",
             self.escrow.maker.as_ref(),
-            extra_seed.as_ref(),
+            id.as_ref(),
             &self.escrow.bump.to_le_bytes(),
         ]]; // self.maker.key()  --> error:: temporary value dropped while borrowing
 
@@ -81,17 +88,18 @@ impl<'info> CancelOffer<'info> {
         Ok(())
     }
 
-    pub fn close_vault(&mut self, extra_seed: &String) -> Result<()> {
+    pub fn close_vault(&mut self) -> Result<()> {
         let close_accounts = CloseAccount {
             account: self.vault.to_account_info(),
             destination: self.maker.to_account_info(),
             authority: self.escrow.to_account_info(),
         };
 
+        let id = self.escrow.id.to_le_bytes(); // binding to prevent temporary value dropped errors
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"escrow",
             self.escrow.maker.as_ref(),
-            extra_seed.as_ref(),
+            id.as_ref(),
             &self.escrow.bump.to_le_bytes(),
         ]];
 
@@ -103,6 +111,9 @@ impl<'info> CancelOffer<'info> {
 
         close_account(close_context)?;
 
+        // escrow itself is closed by its `close` constraint
+        self.registry.close_offer(self.escrow.id);
+
         Ok(())
     }
 
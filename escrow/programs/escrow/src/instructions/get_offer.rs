@@ -1,23 +1,58 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
-use crate::state::Escrow;
+use crate::state::{Escrow, OfferView};
 
 #[derive(Accounts)]
-#[instruction(maker_key: Pubkey, extra_seed: String,)]
+#[instruction(maker_key: Pubkey, id: u64)]
 pub struct GetOffer<'info> {
+    // read only :: nothing is written, so simulations and cpi callers don't need a writable lock
     #[account(
-        mut,
-        seeds = [b"escrow", maker_key.as_ref(), extra_seed.as_ref()],
+        seeds = [b"escrow", maker_key.as_ref(), id.to_le_bytes().as_ref()],
         bump = maker_escrow.bump,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub maker_escrow: Account<'info, Escrow>,
 
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = maker_escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program = token_program
     )]
-    maker_escrow: Account<'info, Escrow>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> GetOffer<'info> {
-    pub fn get_maker_escrow_data_log(&mut self) -> Result<()> {
-        msg!("{:?}", self.maker_escrow); // in the test check how this shows the data, user gets what data represents ? fine: refactor_code || use individual logging on self.maker_escrow.data_value[0]....n
+    // returned through `set_return_data` by anchor
+    pub fn get_offer_view(&self) -> Result<OfferView> {
+        let escrow = &self.maker_escrow;
 
-        Ok(())
+        Ok(OfferView {
+            maker: escrow.maker,
+            id: escrow.id,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            offered_amount: escrow.offered_amount,
+            expected_amount: escrow.expected_amount,
+            expires_at: escrow.expires_at,
+            allowed_takers: escrow.allowed_takers,
+            pricing: escrow.pricing,
+            vault_balance: self.vault.amount,
+            mint_a_decimals: self.mint_a.decimals,
+            mint_b_decimals: self.mint_b.decimals,
+        })
     }
 }
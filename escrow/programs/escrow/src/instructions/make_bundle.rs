@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_2022::{transfer_checked, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowErrors,
+    state::{AllowedTakers, Bundle, BundleAsset, OfferRegistry},
+};
+
+#[derive(Accounts)]
+pub struct MakeBundle<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    // same counter as single asset offers, so ids never collide between the two
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = 8 + OfferRegistry::INIT_SPACE,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Bundle::INIT_SPACE,
+        seeds = [b"bundle", maker.key().as_ref(), registry.next_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    // @note :: every asset of the bundle (both sides) has to live under this one token program
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeBundle<'info> {
+    // remaining accounts per offered asset, in offered order: [mint, maker_ata, vault]
+    // vault == ata of the bundle for that mint, created here
+    pub const ACCOUNTS_PER_ASSET: usize = 3;
+
+    pub fn initialize_bundle(
+        &mut self,
+        bump: &MakeBundleBumps,
+        offered: Vec<BundleAsset>,
+        expected: Vec<BundleAsset>,
+        allowed_takers: Option<AllowedTakers>,
+    ) -> Result<()> {
+        Bundle::validate_assets(&offered)?;
+        Bundle::validate_assets(&expected)?;
+
+        if self.registry.maker == Pubkey::default() {
+            self.registry.maker = self.maker.key();
+            self.registry.bump = bump.registry;
+        }
+        let id = self.registry.open_offer()?;
+
+        self.bundle.set_inner(Bundle {
+            maker: self.maker.key(),
+            id,
+            offered,
+            expected,
+            allowed_takers,
+            bump: bump.bundle,
+        });
+
+        Ok(())
+    }
+
+    // any failing asset reverts the whole bundle
+    pub fn deposit_assets(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let offered = self.bundle.offered.clone();
+        require!(
+            remaining_accounts.len() == offered.len() * Self::ACCOUNTS_PER_ASSET,
+            EscrowErrors::InvalidBundleAccounts
+        );
+
+        for (accounts, asset) in remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_ASSET)
+            .zip(offered)
+        {
+            self.deposit_asset(accounts, asset)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn deposit_asset(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        asset: BundleAsset,
+    ) -> Result<()> {
+        let [mint, maker_ata, vault] = accounts else {
+            return err!(EscrowErrors::InvalidBundleAccounts);
+        };
+        require_keys_eq!(mint.key(), asset.mint, EscrowErrors::InvalidBundleAccount);
+        require_keys_eq!(
+            *mint.owner,
+            self.token_program.key(),
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+        let maker_token = InterfaceAccount::<TokenAccount>::try_from(maker_ata)?;
+        require_keys_eq!(
+            maker_token.owner,
+            self.maker.key(),
+            EscrowErrors::InvalidBundleAccount
+        );
+        require_keys_eq!(
+            maker_token.mint,
+            asset.mint,
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        require_keys_eq!(
+            vault.key(),
+            get_associated_token_address_with_program_id(
+                &self.bundle.key(),
+                &asset.mint,
+                &self.token_program.key()
+            ),
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        let create_accounts = Create {
+            payer: self.maker.to_account_info(),
+            associated_token: vault.clone(),
+            authority: self.bundle.to_account_info(),
+            mint: mint.clone(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+
+        let create_context = CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            create_accounts,
+        );
+
+        // idempotent :: anyone can create the bundle's ata up front, which would otherwise block the bundle
+        create_idempotent(create_context)?;
+
+        let vault_token = InterfaceAccount::<TokenAccount>::try_from(vault)?;
+        require_keys_eq!(
+            vault_token.owner,
+            self.bundle.key(),
+            EscrowErrors::InvalidBundleAccount
+        );
+        require_keys_eq!(
+            vault_token.mint,
+            asset.mint,
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        let transfer_accounts = TransferChecked {
+            from: maker_ata.clone(),
+            mint: mint.clone(),
+            to: vault.clone(),
+            authority: self.maker.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_context, asset.amount, mint_account.decimals)?;
+
+        Ok(())
+    }
+}
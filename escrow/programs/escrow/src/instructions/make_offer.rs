@@ -5,10 +5,12 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::state::Escrow;
+use crate::{
+    error::EscrowErrors,
+    state::{AllowedTakers, Escrow, OfferRegistry, OraclePricing},
+};
 
 #[derive(Accounts)]
-#[instruction( extra_seed: String)]
 pub struct MakeOffer<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
@@ -32,14 +34,23 @@ pub struct MakeOffer<'info> {
     )]
     pub maker_offered_ata: InterfaceAccount<'info, TokenAccount>,
 
-    // create an escrow
+    // maker's offer counter, created along with the first offer
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = 8 + OfferRegistry::INIT_SPACE,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
+    // create an escrow :: registry must come first, its counter is part of the seeds
     #[account(
         init,
         payer = maker,
         space = 8 + Escrow::INIT_SPACE,
-        seeds = [b"escrow", maker.key().as_ref(), extra_seed.as_ref()],
+        seeds = [b"escrow", maker.key().as_ref(), registry.next_id.to_le_bytes().as_ref()],
         bump,
-
     )]
     pub escrow: Account<'info, Escrow>,
 
@@ -72,13 +83,39 @@ impl<'info> MakeOffer<'info> {
         bump: &MakeOfferBumps,
         deposit_amount: u64,
         expect_amount: u64,
+        expires_at: Option<i64>,
+        allowed_takers: Option<AllowedTakers>,
+        pricing: Option<OraclePricing>,
     ) -> Result<()> {
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                EscrowErrors::InvalidExpiry
+            );
+        }
+
+        // oracle priced offers have no fixed price to fall back on
+        if let Some(pricing) = pricing {
+            pricing.validate()?;
+            require!(expect_amount == 0, EscrowErrors::InvalidPricing);
+        }
+
+        if self.registry.maker == Pubkey::default() {
+            self.registry.maker = self.maker.key();
+            self.registry.bump = bump.registry;
+        }
+        let id = self.registry.open_offer()?;
+
         *self.escrow = Escrow {
             maker: self.maker.key(),
+            id,
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
             offered_amount: deposit_amount,
             expected_amount: expect_amount,
+            expires_at,
+            allowed_takers,
+            pricing,
             bump: bump.escrow,
         }; //@note Instead of dereference you can also use self.escrow.set_inner(Escrow{....})
 
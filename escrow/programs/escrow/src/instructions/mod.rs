@@ -7,5 +7,22 @@ pub use take_offer::*;
 pub mod cancel_offer;
 pub use cancel_offer::*;
 
+pub mod refund_expired;
+pub use refund_expired::*;
+
 pub mod get_offer;
 pub use get_offer::*;
+
+pub mod make_bundle;
+pub use make_bundle::*;
+
+pub mod take_bundle;
+pub use take_bundle::*;
+
+pub mod cancel_bundle;
+pub use cancel_bundle::*;
+
+#[cfg(feature = "mock-oracle")]
+pub mod update_mock_feed;
+#[cfg(feature = "mock-oracle")]
+pub use update_mock_feed::*;
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::EscrowErrors, Escrow, OfferRegistry};
+
+#[derive(Accounts)]
+pub struct RefundExpired<'info> {
+    // anyone can crank expired offers, pays for maker's ata if it was closed in the meantime
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.id.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        close = maker,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_offered_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> RefundExpired<'info> {
+    // same as cancel_offer, minus the maker having to sign
+    pub fn refund(&mut self) -> Result<()> {
+        require!(
+            self.escrow.is_expired(Clock::get()?.unix_timestamp),
+            EscrowErrors::OfferNotExpired
+        );
+
+        let id = self.escrow.id.to_le_bytes(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"escrow",
+            self.escrow.maker.as_ref(),
+            id.as_ref(),
+            &[self.escrow.bump],
+        ]];
+
+        // transfer back whatever is left of the offer from vault to maker
+        let transfer_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+            to: self.maker_offered_ata.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let transfer_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(transfer_context, self.vault.amount, self.mint_a.decimals)?;
+
+        // close vault, escrow is closed by its `close` constraint
+        let close_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.maker.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let close_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        );
+
+        close_account(close_context)?;
+
+        self.registry.close_offer(self.escrow.id);
+
+        Ok(())
+    }
+}
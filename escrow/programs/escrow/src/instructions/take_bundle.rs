@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowErrors,
+    state::{Bundle, BundleAsset, OfferRegistry},
+};
+
+#[derive(Accounts)]
+pub struct TakeBundle<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    // receives the expected assets through its own atas + rent of the bundle and its vaults
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"bundle", maker.key().as_ref(), bundle.id.to_le_bytes().as_ref()],
+        bump = bundle.bump,
+        close = maker,
+    )]
+    pub bundle: Account<'info, Bundle>,
+
+    #[account(
+        mut,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> TakeBundle<'info> {
+    // remaining accounts per offered asset, in offered order: [mint, vault, taker_ata]
+    // followed by, per expected asset, in expected order: [mint, taker_ata, maker_ata]
+    pub const ACCOUNTS_PER_ASSET: usize = 3;
+
+    // both sides settle atomically, any failing asset reverts the whole take
+    pub fn settle(
+        &mut self,
+        proof: &[[u8; 32]],
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        self.bundle.validate_taker(&self.taker.key(), proof)?;
+
+        let (offered, expected) = (self.bundle.offered.clone(), self.bundle.expected.clone());
+        require!(
+            remaining_accounts.len() == (offered.len() + expected.len()) * Self::ACCOUNTS_PER_ASSET,
+            EscrowErrors::InvalidBundleAccounts
+        );
+        let (offered_accounts, expected_accounts) =
+            remaining_accounts.split_at(offered.len() * Self::ACCOUNTS_PER_ASSET);
+
+        for (accounts, asset) in expected_accounts
+            .chunks(Self::ACCOUNTS_PER_ASSET)
+            .zip(expected)
+        {
+            self.pay_asset(accounts, asset)?;
+        }
+        for (accounts, asset) in offered_accounts
+            .chunks(Self::ACCOUNTS_PER_ASSET)
+            .zip(offered)
+        {
+            self.withdraw_and_close_vault(accounts, asset)?;
+        }
+
+        // bundle itself is closed by its `close` constraint
+        self.registry.close_offer(self.bundle.id);
+
+        Ok(())
+    }
+
+    // taker -> maker, for one expected asset
+    pub fn pay_asset(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        asset: BundleAsset,
+    ) -> Result<()> {
+        let [mint, taker_ata, maker_ata] = accounts else {
+            return err!(EscrowErrors::InvalidBundleAccounts);
+        };
+        require_keys_eq!(mint.key(), asset.mint, EscrowErrors::InvalidBundleAccount);
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+
+        // taker_ata is checked by the token program, it has to be signed for by the taker
+        let maker_token = InterfaceAccount::<TokenAccount>::try_from(maker_ata)?;
+        require_keys_eq!(
+            maker_token.owner,
+            self.maker.key(),
+            EscrowErrors::InvalidBundleAccount
+        );
+        require_keys_eq!(
+            maker_token.mint,
+            asset.mint,
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        let transfer_accounts = TransferChecked {
+            from: taker_ata.clone(),
+            mint: mint.clone(),
+            to: maker_ata.clone(),
+            authority: self.taker.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_context, asset.amount, mint_account.decimals)?;
+
+        Ok(())
+    }
+
+    // vault -> taker, for one offered asset, then the emptied vault's rent goes back to maker
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        asset: BundleAsset,
+    ) -> Result<()> {
+        let [mint, vault, taker_ata] = accounts else {
+            return err!(EscrowErrors::InvalidBundleAccounts);
+        };
+        require_keys_eq!(mint.key(), asset.mint, EscrowErrors::InvalidBundleAccount);
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+
+        require_keys_eq!(
+            vault.key(),
+            get_associated_token_address_with_program_id(
+                &self.bundle.key(),
+                &asset.mint,
+                &self.token_program.key()
+            ),
+            EscrowErrors::InvalidBundleAccount
+        );
+        let taker_token = InterfaceAccount::<TokenAccount>::try_from(taker_ata)?;
+        require_keys_eq!(
+            taker_token.owner,
+            self.taker.key(),
+            EscrowErrors::InvalidBundleAccount
+        );
+        require_keys_eq!(
+            taker_token.mint,
+            asset.mint,
+            EscrowErrors::InvalidBundleAccount
+        );
+
+        let id = self.bundle.id.to_le_bytes(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"bundle",
+            self.bundle.maker.as_ref(),
+            id.as_ref(),
+            &[self.bundle.bump],
+        ]];
+
+        // taker gets the whole vault, anything sent to it on top of the asset would otherwise block the close
+        let amount = InterfaceAccount::<TokenAccount>::try_from(vault)?.amount;
+
+        let transfer_accounts = TransferChecked {
+            from: vault.clone(),
+            mint: mint.clone(),
+            to: taker_ata.clone(),
+            authority: self.bundle.to_account_info(),
+        };
+
+        let transfer_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+
+        transfer_checked(transfer_context, amount, mint_account.decimals)?;
+
+        let close_accounts = CloseAccount {
+            account: vault.clone(),
+            destination: self.maker.to_account_info(),
+            authority: self.bundle.to_account_info(),
+        };
+
+        let close_context = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        );
+
+        close_account(close_context)?;
+
+        Ok(())
+    }
+}
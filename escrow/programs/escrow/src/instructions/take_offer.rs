@@ -1,5 +1,3 @@
-use std::io::Take;
-
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -7,10 +5,9 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::Escrow;
+use crate::{error::EscrowErrors, Escrow, OfferRegistry, OraclePricing};
 
 #[derive(Accounts)]
-#[instruction(_extra_seed: String,)]
 pub struct TakeOffer<'info> {
     //@note :: you will need makers_ata_b, vault  &&&& taker_ata_a, taker_ata_b
     #[account(mut)]
@@ -49,15 +46,25 @@ pub struct TakeOffer<'info> {
     )]
     pub mint_b: InterfaceAccount<'info, Mint>,
 
-    // maker escrow
+    // maker escrow :: closed manually once the last token is taken, partial fills keep it open
+    // mints must be the offer's own, otherwise the taker could pay in any worthless mint_b
     #[account(
         mut,
-        seeds = [b"escrow", maker.key().as_ref(), _extra_seed.as_ref()],
+        seeds = [b"escrow", maker.key().as_ref(), escrow.id.to_le_bytes().as_ref()],
         bump = escrow.bump,
-        close = maker,
+        has_one = mint_a,
+        has_one = mint_b,
     )]
     pub escrow: Account<'info, Escrow>,
 
+    // offer id is dropped from here once fully taken
+    #[account(
+        mut,
+        seeds = [b"registry", maker.key().as_ref()],
+        bump = registry.bump,
+    )]
+    pub registry: Account<'info, OfferRegistry>,
+
     // maker vault == ata of escrow --(authorized by maker himself)-- that holds makers offered tokens
     #[account(
         mut,
@@ -76,6 +83,9 @@ pub struct TakeOffer<'info> {
     )]
     pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: only for oracle priced offers, must be the feed the offer is quoted from, owner and layout are checked when quoting
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
     // ata_program , system_program, token_program
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
@@ -83,8 +93,23 @@ pub struct TakeOffer<'info> {
 }
 
 impl<'info> TakeOffer<'info> {
-    // transfer mint_b tokens from taker's ata to the maker's ata
-    pub fn deposit(&mut self) -> Result<()> {
+    // transfer mint_b tokens from taker's ata to the maker's ata, proportional to the taken amount
+    // `max_payment` caps the mint_b the taker pays, oracle quotes can move between signing and landing
+    pub fn deposit(&mut self, amount: u64, max_payment: u64, proof: &[[u8; 32]]) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(!self.escrow.is_expired(now), EscrowErrors::OfferExpired);
+        // negotiated offers can't be sniped by anyone else
+        self.escrow.validate_taker(self.taker.key, proof)?;
+
+        let price = match self.escrow.pricing {
+            Some(pricing) => {
+                self.escrow.validate_take_amount(amount)?;
+                self.quote(pricing, amount, now)?
+            }
+            None => self.escrow.price_of(amount)?,
+        };
+        require!(price <= max_payment, EscrowErrors::PaymentAboveMax);
+
         let transfer_accounts = TransferChecked {
             from: self.taker_ata_b.to_account_info(),
             mint: self.mint_b.to_account_info(),
@@ -95,25 +120,51 @@ impl<'info> TakeOffer<'info> {
         let transfer_context =
             CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
 
-        transfer_checked(
-            transfer_context,
-            self.escrow.expected_amount,
-            self.mint_b.decimals,
-        )?;
+        transfer_checked(transfer_context, price, self.mint_b.decimals)?;
+
+        // escrow now tracks what's left of the offer
+        self.escrow.offered_amount -= amount;
+        if self.escrow.pricing.is_none() {
+            self.escrow.expected_amount -= price;
+        }
 
         Ok(())
     }
 
+    // mint_b amount for `amount` at the offer's feed price
+    pub fn quote(&self, pricing: OraclePricing, amount: u64, now: i64) -> Result<u64> {
+        let feed = self
+            .price_feed
+            .as_ref()
+            .ok_or(EscrowErrors::MissingPriceFeed)?;
+        require_keys_eq!(feed.key(), pricing.feed, EscrowErrors::PriceFeedMismatch);
+
+        pricing.quote(
+            amount,
+            OraclePricing::read_feed(feed)?,
+            now,
+            self.mint_a.decimals,
+            self.mint_b.decimals,
+        )
+    }
+
     //@note@dev :: one of the benefits of doing both close and withdraw operation in single function is that you get to derive signer seeds only once, hence lesser the codesize and more the readability
-    pub fn withdraw_and_close_vault(&mut self, _extra_seed: String) -> Result<()> {
+    pub fn withdraw_and_close_vault(&mut self, amount: u64) -> Result<()> {
+        let id = self.escrow.id.to_le_bytes(); // binding to prevent temporary value dropped errors
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"escrow",
             self.escrow.maker.as_ref(),
-            _extra_seed.as_ref(),
+            id.as_ref(),
             &[self.escrow.bump], //@audit :: &self.escrow.bump.to_le_bytes() works the same ??? test if diff ???
         ]];
 
         // // withdraw mint_a tokens from vault and send them to the taker  ata
+        // closing fill drains the whole vault, tokens sent to it on top of the offer would otherwise block the close
+        let amount = if self.escrow.offered_amount == 0 {
+            self.vault.amount
+        } else {
+            amount
+        };
 
         let transfer_accounts = TransferChecked {
             from: self.vault.to_account_info(),
@@ -128,12 +179,12 @@ impl<'info> TakeOffer<'info> {
             signer_seeds,
         );
 
-        transfer_checked(
-            transfer_context,
-            self.vault.amount,
-            // self.escrow.offered_amount,  // @note :: same thing since we correctly updated this state during making offer
-            self.mint_b.decimals,
-        )?;
+        transfer_checked(transfer_context, amount, self.mint_a.decimals)?;
+
+        // partially filled :: vault and escrow stay open for the next taker
+        if self.escrow.offered_amount > 0 {
+            return Ok(());
+        }
 
         // // // Now close vault
 
@@ -151,6 +202,10 @@ impl<'info> TakeOffer<'info> {
 
         close_account(close_context)?;
 
+        // and the escrow, rent back to maker
+        self.registry.close_offer(self.escrow.id);
+        self.escrow.close(self.maker.to_account_info())?;
+
         Ok(())
     }
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MockPriceFeed;
+
+#[derive(Accounts)]
+pub struct UpdateMockFeed<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // one feed per authority, anyone else's feed can't be touched since the signer is part of the seeds
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MockPriceFeed::INIT_SPACE,
+        seeds = [b"mock_feed", authority.key().as_ref()],
+        bump,
+    )]
+    pub price_feed: Account<'info, MockPriceFeed>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateMockFeed<'info> {
+    pub fn update_feed(&mut self, bump: &UpdateMockFeedBumps, price: i64, expo: i32) -> Result<()> {
+        self.price_feed.set_inner(MockPriceFeed {
+            authority: self.authority.key(),
+            price,
+            expo,
+            publish_time: Clock::get()?.unix_timestamp,
+            bump: bump.price_feed,
+        });
+
+        Ok(())
+    }
+}
@@ -1,6 +1,14 @@
+// allowed only for the idl code `#[program]` generates at the crate root (uses deprecated `realloc`)
+// our own modules below warn again
+#![allow(deprecated)]
+
+#[warn(deprecated)]
 pub mod constants;
+#[warn(deprecated)]
 pub mod error;
+#[warn(deprecated)]
 pub mod instructions;
+#[warn(deprecated)]
 pub mod state;
 
 use anchor_lang::prelude::*;
@@ -11,47 +19,110 @@ pub use state::*;
 
 declare_id!("3MZi8MF8xzf7JusYnsUgW3Dia3qNgG1n7jE1Hm19svMi");
 
+#[warn(deprecated)]
 #[program]
 pub mod escrow {
     // use anchor_lang::prelude::borsh::de;
 
     use super::*;
 
+    // offer id comes from maker's registry counter, see `OfferRegistry`
     pub fn make_offer(
         ctx: Context<MakeOffer>,
         deposit_amount: u64,
         expect_amount: u64,
-        _extra_seed: String,
+        expires_at: Option<i64>,
+        allowed_takers: Option<AllowedTakers>,
+        pricing: Option<OraclePricing>,
     ) -> Result<()> {
-        ctx.accounts
-            .initialize_escrow(&ctx.bumps, deposit_amount, expect_amount)?;
+        ctx.accounts.initialize_escrow(
+            &ctx.bumps,
+            deposit_amount,
+            expect_amount,
+            expires_at,
+            allowed_takers,
+            pricing,
+        )?;
         ctx.accounts.deposit_tokens(deposit_amount)?;
         Ok(())
     }
 
-    pub fn take_offer(ctx: Context<TakeOffer>, _extra_seed: String) -> Result<()> {
-        ctx.accounts.deposit()?;
-        ctx.accounts.withdraw_and_close_vault(_extra_seed)?;
+    // taker can take any part of the remaining offer, vault + escrow are closed once all of it is taken
+    // oracle priced offers are quoted from `price_feed` at take time, fixed ones ignore it
+    // `price_feed` is a pyth receiver price update, or a mock feed in builds with the `mock-oracle` feature
+    // `max_payment` is the most mint_b the taker agrees to pay for `amount`, the take fails above it
+    // `proof` is the merkle proof of the taker for offers restricted to a merkle root, empty otherwise
+    pub fn take_offer(
+        ctx: Context<TakeOffer>,
+        amount: u64,
+        max_payment: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.deposit(amount, max_payment, &proof)?;
+        ctx.accounts.withdraw_and_close_vault(amount)?;
         Ok(())
     }
 
-    pub fn cancel_offer(ctx: Context<CancelOffer>, extra_seed: String) -> Result<()> {
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
         // transfer back offered tokens from vault to maker
-        ctx.accounts.withdraw_offered_amounts(&extra_seed)?;
+        ctx.accounts.withdraw_offered_amounts()?;
         // close escrow + vault account
-        ctx.accounts.close_vault(&extra_seed)?;
+        ctx.accounts.close_vault()?;
 
         Ok(())
     }
 
-    // Maybe create a helper that gets escrows made by certain users >>>> make sure escrow is dervied from multiple seeds out  of which one must be arbitary string such that users can create multiple offers
+    // anyone can crank an expired offer, tokens go back to maker's ata and rent to maker
+    pub fn refund_expired(ctx: Context<RefundExpired>) -> Result<()> {
+        ctx.accounts.refund()?;
+        Ok(())
+    }
 
-    pub fn get_offer_of(
-        ctx: Context<GetOffer>,
-        _maker_key: Pubkey,
-        _extra_seed: String,
+    // maker's open offer ids can be read from the `registry` account at [b"registry", maker]
+    // borsh serialized `OfferView` in the transaction return data, readable from cpi or simulation
+    pub fn get_offer_of(ctx: Context<GetOffer>, _maker_key: Pubkey, _id: u64) -> Result<OfferView> {
+        ctx.accounts.get_offer_view()
+    }
+
+    // basket for basket offer, each offered asset sits in the bundle's own ata of that mint
+    // remaining accounts per offered asset: [mint, maker_ata, vault], see `MakeBundle`
+    pub fn make_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeBundle<'info>>,
+        offered: Vec<BundleAsset>,
+        expected: Vec<BundleAsset>,
+        allowed_takers: Option<AllowedTakers>,
     ) -> Result<()> {
-        ctx.accounts.get_maker_escrow_data_log()
+        ctx.accounts
+            .initialize_bundle(&ctx.bumps, offered, expected, allowed_takers)?;
+        ctx.accounts.deposit_assets(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    // all or nothing :: every expected asset is paid and every vault emptied + closed in one go
+    // remaining accounts: offered [mint, vault, taker_ata] then expected [mint, taker_ata, maker_ata]
+    pub fn take_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TakeBundle<'info>>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.settle(&proof, ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    // remaining accounts per offered asset: [mint, vault, maker_ata]
+    pub fn cancel_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelBundle<'info>>,
+    ) -> Result<()> {
+        ctx.accounts
+            .withdraw_offered_assets(ctx.remaining_accounts)?;
+        Ok(())
+    }
+
+    // creates or updates the caller's mock feed at [b"mock_feed", authority], publish_time is set to now
+    // @note :: stand-in for a real oracle on localnet / in tests, only built with the `mock-oracle` feature
+    #[cfg(feature = "mock-oracle")]
+    pub fn update_mock_feed(ctx: Context<UpdateMockFeed>, price: i64, expo: i32) -> Result<()> {
+        ctx.accounts.update_feed(&ctx.bumps, price, expo)?;
+        Ok(())
     }
 
     //@Todo: update code with professional natspec and most importantly thorough test scripts
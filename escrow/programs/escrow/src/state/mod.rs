@@ -1,13 +1,352 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::{error::EscrowErrors, MAX_BUNDLE_ASSETS, MAX_OPEN_OFFERS, PYTH_RECEIVER_ID};
 
 #[account]
-#[derive(InitSpace, Debug)] // so that you can log this entirely via get_offer crate !!!
+#[derive(InitSpace, Debug)]
 pub struct Escrow {
     pub maker: Pubkey,
-    pub mint_a: Pubkey,       // Token Mint That user is offering
-    pub mint_b: Pubkey,       // Token MInt that user is expecting to get
-    pub offered_amount: u64,  // token amounts the maker is offering (mint_a)
-    pub expected_amount: u64, // token amounts the maker wants in return (mint_b)
+    pub id: u64,        // taken from maker's registry counter, part of the escrow seeds
+    pub mint_a: Pubkey, // Token Mint That user is offering
+    pub mint_b: Pubkey, // Token MInt that user is expecting to get
+    pub offered_amount: u64, // token amounts the maker is still offering (mint_a), decreases with every partial fill
+    pub expected_amount: u64, // token amounts the maker still wants in return (mint_b), decreases with every partial fill, 0 for oracle priced offers
+    pub expires_at: Option<i64>, // offer can't be taken from here on and anyone can refund it to the maker, None never expires
+    pub allowed_takers: Option<AllowedTakers>, // negotiated offers only these takers can take, None anyone can
+    pub pricing: Option<OraclePricing>, // price is quoted from a feed at take time, None the fixed expected_amount applies
     pub bump: u8,
     // @note  : Maybe later, try storing maker's both ata in order to reduce Cu during taking/cancelling of offer /// dont forget doing this will increase cu for making an offer >>>> Just try it maybe
 }
+
+// basket for basket offer :: settled all at once, no partial fills
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Bundle {
+    pub maker: Pubkey,
+    pub id: u64, // shares the maker's registry counter with single asset offers
+    #[max_len(MAX_BUNDLE_ASSETS)]
+    pub offered: Vec<BundleAsset>, // each held in the bundle's ata of that mint
+    #[max_len(MAX_BUNDLE_ASSETS)]
+    pub expected: Vec<BundleAsset>,
+    pub allowed_takers: Option<AllowedTakers>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct BundleAsset {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl Bundle {
+    // both sides hold 1..=MAX_BUNDLE_ASSETS distinct mints with non zero amounts
+    pub fn validate_assets(assets: &[BundleAsset]) -> Result<()> {
+        require!(
+            (1..=MAX_BUNDLE_ASSETS).contains(&assets.len()),
+            EscrowErrors::InvalidBundle
+        );
+        for (i, asset) in assets.iter().enumerate() {
+            require!(
+                asset.amount > 0 && assets[..i].iter().all(|other| other.mint != asset.mint),
+                EscrowErrors::InvalidBundle
+            );
+        }
+        Ok(())
+    }
+
+    pub fn validate_taker(&self, taker: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        if let Some(allowed_takers) = self.allowed_takers {
+            allowed_takers.validate(taker, proof)?;
+        }
+        Ok(())
+    }
+}
+
+// returned by `get_offer_of` :: escrow state plus what clients otherwise need 3 more account fetches for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OfferView {
+    pub maker: Pubkey,
+    pub id: u64,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub offered_amount: u64,
+    pub expected_amount: u64,
+    pub expires_at: Option<i64>,
+    pub allowed_takers: Option<AllowedTakers>,
+    pub pricing: Option<OraclePricing>,
+    pub vault_balance: u64, // mint_a tokens actually held by the vault
+    pub mint_a_decimals: u8,
+    pub mint_b_decimals: u8,
+}
+
+// one per maker :: hands out offer ids and lists the open ones so clients don't need getProgramAccounts
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct OfferRegistry {
+    pub maker: Pubkey,
+    pub next_id: u64, // id of the maker's next offer, never reused
+    #[max_len(MAX_OPEN_OFFERS)]
+    pub open_offers: Vec<u64>, // ids of offers not yet fully taken, cancelled or refunded
+    pub bump: u8,
+}
+
+impl OfferRegistry {
+    // registers a new offer and returns its id
+    pub fn open_offer(&mut self) -> Result<u64> {
+        require!(
+            self.open_offers.len() < MAX_OPEN_OFFERS,
+            EscrowErrors::TooManyOpenOffers
+        );
+
+        let id = self.next_id;
+        self.open_offers.push(id);
+        self.next_id += 1;
+
+        Ok(id)
+    }
+
+    pub fn close_offer(&mut self, id: u64) {
+        self.open_offers.retain(|open| *open != id);
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum AllowedTakers {
+    Taker(Pubkey), // single designated taker
+    // sha256 merkle root of allowed takers :: leaf = hash(taker), node = hash(min(a, b), max(a, b))
+    MerkleRoot([u8; 32]),
+}
+
+impl AllowedTakers {
+    // `proof` is ignored for a designated taker
+    pub fn validate(&self, taker: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        match self {
+            AllowedTakers::Taker(allowed) => {
+                require_keys_eq!(*allowed, *taker, EscrowErrors::TakerNotAllowed)
+            }
+            AllowedTakers::MerkleRoot(root) => {
+                let leaf = hashv(&[taker.as_ref()]).to_bytes();
+                let computed = proof.iter().fold(leaf, |node, sibling| {
+                    if node <= *sibling {
+                        hashv(&[&node, sibling]).to_bytes()
+                    } else {
+                        hashv(&[sibling, &node]).to_bytes()
+                    }
+                });
+                require!(computed == *root, EscrowErrors::TakerNotAllowed);
+            }
+        }
+        Ok(())
+    }
+}
+
+// quote relative to market :: mint_b per mint_a from the feed, plus the maker's premium (or discount when negative)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct OraclePricing {
+    pub feed: Pubkey,
+    pub spread_bps: i16,    // +50 == 0.5% above the feed price, -50 == 0.5% below
+    pub max_staleness: u32, // seconds since the feed's publish_time after which takes are refused
+    pub max_confidence_bps: u16, // confidence interval wider than this share of the price refuses takes
+}
+
+// price read from a feed account, whichever kind it is
+#[derive(Clone, Copy, Debug)]
+pub struct FeedPrice {
+    pub price: i64,
+    pub conf: u64, // confidence interval, same exponent as price
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePricing {
+    pub const BPS_DENOMINATOR: i32 = 10_000;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            (self.spread_bps as i32) > -Self::BPS_DENOMINATOR
+                && self.max_staleness > 0
+                && self.max_confidence_bps > 0,
+            EscrowErrors::InvalidPricing
+        );
+        Ok(())
+    }
+
+    // reads the offer's feed account :: pyth receiver price updates, or the mock feed in `mock-oracle` builds
+    pub fn read_feed(feed: &AccountInfo) -> Result<FeedPrice> {
+        if feed.owner == &PYTH_RECEIVER_ID {
+            let data = feed.try_borrow_data()?;
+            return PriceUpdateV2::try_from_slice_checked(&data)?.feed_price();
+        }
+
+        #[cfg(feature = "mock-oracle")]
+        if feed.owner == &crate::ID {
+            let data = feed.try_borrow_data()?;
+            return Ok(MockPriceFeed::try_deserialize(&mut &data[..])?.feed_price());
+        }
+
+        err!(EscrowErrors::OracleNotSupported)
+    }
+
+    // mint_b amount for `amount` of mint_a at the current feed price, rounded up in maker's favour
+    // feed price is in whole tokens (price * 10^expo), so both mints' decimals are folded into the exponent
+    pub fn quote(
+        &self,
+        amount: u64,
+        feed: FeedPrice,
+        now: i64,
+        decimals_a: u8,
+        decimals_b: u8,
+    ) -> Result<u64> {
+        require!(
+            now.saturating_sub(feed.publish_time) <= self.max_staleness as i64,
+            EscrowErrors::StalePrice
+        );
+        require!(feed.price > 0, EscrowErrors::InvalidPrice);
+        // a wide confidence interval means the market price isn't known well enough to fill against
+        require!(
+            (feed.conf as u128) * (Self::BPS_DENOMINATOR as u128)
+                <= (feed.price as u128) * (self.max_confidence_bps as u128),
+            EscrowErrors::PriceUncertain
+        );
+
+        let spread = (Self::BPS_DENOMINATOR + self.spread_bps as i32) as u128;
+        let numerator = (amount as u128)
+            .checked_mul(feed.price as u128)
+            .and_then(|n| n.checked_mul(spread))
+            .ok_or(EscrowErrors::InvalidPrice)?;
+
+        let exponent = feed.expo + decimals_b as i32 - decimals_a as i32;
+        let scale = 10u128
+            .checked_pow(exponent.unsigned_abs())
+            .ok_or(EscrowErrors::InvalidPrice)?;
+
+        let price = if exponent >= 0 {
+            numerator
+                .checked_mul(scale)
+                .ok_or(EscrowErrors::InvalidPrice)?
+                .div_ceil(Self::BPS_DENOMINATOR as u128)
+        } else {
+            numerator.div_ceil(
+                (Self::BPS_DENOMINATOR as u128)
+                    .checked_mul(scale)
+                    .ok_or(EscrowErrors::InvalidPrice)?,
+            )
+        };
+
+        u64::try_from(price).map_err(|_| error!(EscrowErrors::InvalidPrice))
+    }
+}
+
+// pyth receiver's price update account, what oracle priced offers are quoted from on a deployed program
+// laid out by hand, the receiver sdk isn't a dependency :: discriminator + borsh, same as any anchor account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub price_message: PriceFeedMessage,
+    pub posted_slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 }, // checked against only some of the wormhole guardians
+    Full,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+impl PriceUpdateV2 {
+    // sha256("account:PriceUpdateV2")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+    pub fn try_from_slice_checked(data: &[u8]) -> Result<Self> {
+        let mut data = data
+            .strip_prefix(&Self::DISCRIMINATOR)
+            .ok_or(EscrowErrors::OracleNotSupported)?;
+        Self::deserialize(&mut data).map_err(|_| error!(EscrowErrors::OracleNotSupported))
+    }
+
+    // partially verified updates could be forged by a few guardians, only fully verified ones are trusted
+    pub fn feed_price(&self) -> Result<FeedPrice> {
+        require!(
+            self.verification_level == VerificationLevel::Full,
+            EscrowErrors::UnverifiedPrice
+        );
+
+        Ok(FeedPrice {
+            price: self.price_message.price,
+            conf: self.price_message.conf,
+            expo: self.price_message.exponent,
+            publish_time: self.price_message.publish_time,
+        })
+    }
+}
+
+// local stand-in for an oracle price account (pyth style price/expo/publish_time), for tests and localnet
+// anyone can run a feed under their own key :: takers should check which feed an offer is quoted from
+// only built with the `mock-oracle` feature, a deployed program only quotes from pyth price updates
+#[cfg(feature = "mock-oracle")]
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct MockPriceFeed {
+    pub authority: Pubkey,
+    pub price: i64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub bump: u8,
+}
+
+#[cfg(feature = "mock-oracle")]
+impl MockPriceFeed {
+    // published by hand, so there's no confidence interval to speak of
+    pub fn feed_price(&self) -> FeedPrice {
+        FeedPrice {
+            price: self.price,
+            conf: 0,
+            expo: self.expo,
+            publish_time: self.publish_time,
+        }
+    }
+}
+
+impl Escrow {
+    pub fn validate_taker(&self, taker: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        if let Some(allowed_takers) = self.allowed_takers {
+            allowed_takers.validate(taker, proof)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
+
+    pub fn validate_take_amount(&self, amount: u64) -> Result<()> {
+        require!(
+            amount > 0 && amount <= self.offered_amount,
+            EscrowErrors::InvalidTakeAmount
+        );
+        Ok(())
+    }
+
+    // mint_b amount a taker pays for `amount` of mint_a, keeping the offer's price
+    // rounded up so that partial fills never pay the maker less than the offered rate
+    pub fn price_of(&self, amount: u64) -> Result<u64> {
+        self.validate_take_amount(amount)?;
+
+        let price =
+            (amount as u128 * self.expected_amount as u128).div_ceil(self.offered_amount as u128); // amount * expected / offered, rounded up
+
+        Ok(price as u64)
+    }
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use common::*;
+use escrow::{error::EscrowErrors, BundleAsset};
+use solana_sdk::signature::Signer;
+
+/// 100 mint_a and the nft for 50 mint_b and 7 mint_d
+fn assets(env: &Env) -> (Vec<BundleAsset>, Vec<BundleAsset>) {
+    let offered = vec![
+        BundleAsset {
+            mint: env.mint_a,
+            amount: 100,
+        },
+        BundleAsset {
+            mint: env.nft_mint,
+            amount: 1,
+        },
+    ];
+    let expected = vec![
+        BundleAsset {
+            mint: env.mint_b,
+            amount: 50,
+        },
+        BundleAsset {
+            mint: env.mint_d,
+            amount: 7,
+        },
+    ];
+    (offered, expected)
+}
+
+#[tokio::test]
+async fn make_bundle_rejects_invalid_assets() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let (offered, expected) = assets(&env);
+
+    let duplicated = vec![offered[0], offered[0]];
+    let ix = env.make_bundle_ix(0, duplicated, expected.clone());
+    let result = env.process(&[ix], &[&maker]).await;
+    assert_error(result, EscrowErrors::InvalidBundle);
+
+    // accounts for only the first offered asset
+    let mut ix = env.make_bundle_ix(0, offered, expected);
+    ix.accounts.truncate(ix.accounts.len() - 3);
+    let result = env.process(&[ix], &[&maker]).await;
+    assert_error(result, EscrowErrors::InvalidBundleAccounts);
+}
+
+#[tokio::test]
+async fn take_bundle_swaps_all_assets() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    let (m, t) = (maker.pubkey(), taker.pubkey());
+    let (offered, expected) = assets(&env);
+
+    // a single asset offer takes id 0, bundles share the registry counter
+    env.process(&env.make_offer_ixs(0, 10, 5, None), &[&maker])
+        .await
+        .unwrap();
+    let ix = env.make_bundle_ix(1, offered.clone(), expected.clone());
+    env.process(&[ix], &[&maker]).await.unwrap();
+
+    let bundle = env.bundle_address(1);
+    assert_eq!(env.token_amount(ata(&bundle, &env.mint_a)).await, Some(100));
+    assert_eq!(env.token_amount(ata(&bundle, &env.nft_mint)).await, Some(1));
+
+    // mint_b paid back to the taker instead of the maker, expected accounts come last
+    let mut ix = env.take_bundle_ix(1, &offered, &expected);
+    let maker_ata_b = ix.accounts.len() - 4;
+    ix.accounts[maker_ata_b].pubkey = ata(&t, &env.mint_b);
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::InvalidBundleAccount);
+
+    let ix = env.take_bundle_ix(1, &offered, &expected);
+    env.process(&[ix], &[&taker]).await.unwrap();
+
+    assert_eq!(env.token_amount(ata(&t, &env.mint_a)).await, Some(100));
+    assert_eq!(env.token_amount(ata(&t, &env.nft_mint)).await, Some(1));
+    assert_eq!(env.token_amount(ata(&m, &env.mint_b)).await, Some(50));
+    assert_eq!(env.token_amount(ata(&m, &env.mint_d)).await, Some(7));
+    assert!(env.account(bundle).await.is_none());
+    assert!(env.account(ata(&bundle, &env.mint_a)).await.is_none());
+    assert_eq!(env.registry().await.open_offers, vec![0]);
+}
+
+#[tokio::test]
+async fn cancel_bundle_returns_assets() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let (offered, expected) = assets(&env);
+    let ix = env.make_bundle_ix(0, offered.clone(), expected);
+    env.process(&[ix], &[&maker]).await.unwrap();
+
+    let maker_ata = ata(&maker.pubkey(), &env.mint_a);
+    assert_eq!(env.token_amount(maker_ata).await, Some(MAKER_A - 100));
+
+    env.process(&[env.cancel_bundle_ix(0, &offered)], &[&maker])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_amount(maker_ata).await, Some(MAKER_A));
+    assert_eq!(
+        env.token_amount(ata(&maker.pubkey(), &env.nft_mint)).await,
+        Some(1)
+    );
+    assert!(env.account(env.bundle_address(0)).await.is_none());
+    assert!(env.registry().await.open_offers.is_empty());
+}
+
+#[tokio::test]
+async fn make_bundle_accepts_precreated_vault() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let (offered, expected) = assets(&env);
+
+    // anyone can create the bundle's ata before the maker gets to it
+    let bundle = env.bundle_address(0);
+    let ix = env.create_ata_ix(&bundle, &env.mint_a);
+    env.process(&[ix], &[]).await.unwrap();
+
+    let ix = env.make_bundle_ix(0, offered, expected);
+    env.process(&[ix], &[&maker]).await.unwrap();
+    assert_eq!(env.token_amount(ata(&bundle, &env.mint_a)).await, Some(100));
+}
+
+#[tokio::test]
+async fn take_bundle_drains_surplus_to_taker() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    let (offered, expected) = assets(&env);
+    let ix = env.make_bundle_ix(0, offered.clone(), expected.clone());
+    env.process(&[ix], &[&maker]).await.unwrap();
+
+    // tokens sent to a vault outside of the bundle must not block its close
+    let (mint_a, vault) = (env.mint_a, ata(&env.bundle_address(0), &env.mint_a));
+    env.send_tokens(&maker, mint_a, vault, 5).await;
+
+    let ix = env.take_bundle_ix(0, &offered, &expected);
+    env.process(&[ix], &[&taker]).await.unwrap();
+
+    assert_eq!(
+        env.token_amount(ata(&taker.pubkey(), &mint_a)).await,
+        Some(105)
+    );
+    assert!(env.account(vault).await.is_none());
+}
+
+#[tokio::test]
+async fn cancel_bundle_drains_surplus_to_maker() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let (offered, expected) = assets(&env);
+    let ix = env.make_bundle_ix(0, offered.clone(), expected);
+    env.process(&[ix], &[&maker]).await.unwrap();
+
+    let (mint_a, vault) = (env.mint_a, ata(&env.bundle_address(0), &env.mint_a));
+    env.send_tokens(&maker, mint_a, vault, 5).await;
+
+    env.process(&[env.cancel_bundle_ix(0, &offered)], &[&maker])
+        .await
+        .unwrap();
+
+    let maker_ata = ata(&maker.pubkey(), &mint_a);
+    assert_eq!(env.token_amount(maker_ata).await, Some(MAKER_A));
+    assert!(env.account(vault).await.is_none());
+}
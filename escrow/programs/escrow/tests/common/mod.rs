@@ -0,0 +1,636 @@
+#![allow(dead_code)]
+
+use anchor_lang::{
+    prelude::*, solana_program::program_pack::Pack, system_program, AccountDeserialize,
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, spl_associated_token_account},
+    token::spl_token,
+};
+use escrow::{
+    error::EscrowErrors, AllowedTakers, BundleAsset, Escrow, OfferRegistry, OraclePricing,
+    PriceFeedMessage, PriceUpdateV2, VerificationLevel, PYTH_RECEIVER_ID,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+pub const MAKER_A: u64 = 1_000; // mint_a the maker starts with
+pub const TAKER_B: u64 = 1_000_000; // mint_b the taker starts with
+pub const DECIMALS_A: u8 = 6;
+pub const DECIMALS_B: u8 = 9;
+
+// anchor's entrypoint ties the accounts slice to the `'info` lifetime, which doesn't fit `processor!`
+fn entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    escrow::entry(program_id, accounts, data)
+}
+
+pub struct Env {
+    pub ctx: ProgramTestContext,
+    pub payer: Keypair, // pays fees and rent of the cranks, so maker and taker balances stay exact
+    pub maker: Keypair,
+    pub taker: Keypair,
+    pub other: Keypair, // second taker, holding mint_b as well
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub nft_mint: Pubkey, // held by the maker, offered in bundles
+    pub mint_d: Pubkey,   // held by the taker, expected in bundles
+}
+
+impl Env {
+    /// maker holding mint_a and an nft, taker and other holding mint_b, taker holding mint_d
+    /// every wallet has an empty ata of every mint it can receive
+    pub async fn new() -> Self {
+        let mut test = ProgramTest::new("escrow", escrow::ID, processor!(entry));
+        test.prefer_bpf(false);
+
+        let payer = Keypair::new();
+        let maker = Keypair::new();
+        let taker = Keypair::new();
+        let other = Keypair::new();
+        let mint_a = Pubkey::new_unique();
+        let mint_b = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let mint_d = Pubkey::new_unique();
+
+        for wallet in [&payer, &maker, &taker, &other] {
+            test.add_account(wallet.pubkey(), system_account(10_000_000_000));
+        }
+        test.add_account(mint_a, mint_account(DECIMALS_A, MAKER_A));
+        test.add_account(mint_b, mint_account(DECIMALS_B, 2 * TAKER_B));
+        test.add_account(nft_mint, mint_account(0, 1));
+        test.add_account(mint_d, mint_account(2, 7));
+
+        let balances = [
+            (&maker, mint_a, MAKER_A),
+            (&maker, mint_b, 0),
+            (&maker, nft_mint, 1),
+            (&maker, mint_d, 0),
+            (&taker, mint_a, 0),
+            (&taker, mint_b, TAKER_B),
+            (&taker, nft_mint, 0),
+            (&taker, mint_d, 7),
+            (&other, mint_a, 0),
+            (&other, mint_b, TAKER_B),
+        ];
+        for (wallet, mint, amount) in balances {
+            test.add_account(
+                get_associated_token_address(&wallet.pubkey(), &mint),
+                token_account(&mint, &wallet.pubkey(), amount),
+            );
+        }
+
+        let ctx = test.start_with_context().await;
+
+        Env {
+            ctx,
+            payer,
+            maker,
+            taker,
+            other,
+            mint_a,
+            mint_b,
+            nft_mint,
+            mint_d,
+        }
+    }
+
+    /// sends `ixs` paid by the payer, with `signers` on top of it
+    pub async fn process(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// return data of `ixs`, without landing them
+    pub async fn simulate(&mut self, ixs: &[Instruction]) -> Vec<u8> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+
+        let result = self
+            .ctx
+            .banks_client
+            .simulate_transaction(tx)
+            .await
+            .unwrap();
+        result.result.unwrap().unwrap();
+        result.simulation_details.unwrap().return_data.unwrap().data
+    }
+
+    pub async fn warp(&mut self, seconds: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// pyth receiver price update at `address`, published now
+    pub async fn set_price_update(
+        &mut self,
+        address: Pubkey,
+        price: i64,
+        conf: u64,
+        expo: i32,
+        verification_level: VerificationLevel,
+    ) {
+        let publish_time = self.now().await;
+        let update = PriceUpdateV2 {
+            write_authority: Pubkey::default(),
+            verification_level,
+            price_message: PriceFeedMessage {
+                feed_id: [0; 32],
+                price,
+                conf,
+                exponent: expo,
+                publish_time,
+                prev_publish_time: publish_time,
+                ema_price: price,
+                ema_conf: conf,
+            },
+            posted_slot: 0,
+        };
+
+        let mut data = PriceUpdateV2::DISCRIMINATOR.to_vec();
+        update.serialize(&mut data).unwrap();
+        self.set_account(address, rent_exempt(data, PYTH_RECEIVER_ID));
+    }
+
+    pub fn set_account(&mut self, address: Pubkey, account: Account) {
+        self.ctx.set_account(&address, &account.into());
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(address).await.unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.account(address)
+            .await
+            .map_or(0, |account| account.lamports)
+    }
+
+    pub async fn token_amount(&mut self, address: Pubkey) -> Option<u64> {
+        let account = self.account(address).await?;
+        Some(
+            spl_token::state::Account::unpack(&account.data)
+                .unwrap()
+                .amount,
+        )
+    }
+
+    /// sends `amount` of `mint` from `from`'s ata straight to `to`, outside the program
+    pub async fn send_tokens(&mut self, from: &Keypair, mint: Pubkey, to: Pubkey, amount: u64) {
+        let ix = spl_token::instruction::transfer(
+            &spl_token::ID,
+            &get_associated_token_address(&from.pubkey(), &mint),
+            &to,
+            &from.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[ix], &[from]).await.unwrap();
+    }
+
+    pub async fn escrow(&mut self, id: u64) -> Option<Escrow> {
+        let account = self.account(self.escrow_address(id)).await?;
+        Some(Escrow::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn escrow_address(&self, id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"escrow", self.maker.pubkey().as_ref(), &id.to_le_bytes()],
+            &escrow::ID,
+        )
+        .0
+    }
+
+    pub async fn registry(&mut self) -> OfferRegistry {
+        let account = self.account(self.registry_address()).await.unwrap();
+        OfferRegistry::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn registry_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"registry", self.maker.pubkey().as_ref()], &escrow::ID).0
+    }
+
+    pub fn vault_address(&self, id: u64) -> Pubkey {
+        get_associated_token_address(&self.escrow_address(id), &self.mint_a)
+    }
+
+    pub fn bundle_address(&self, id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"bundle", self.maker.pubkey().as_ref(), &id.to_le_bytes()],
+            &escrow::ID,
+        )
+        .0
+    }
+
+    #[cfg(feature = "mock-oracle")]
+    pub fn feed_address(authority: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"mock_feed", authority.as_ref()], &escrow::ID).0
+    }
+
+    //=====================
+    //    offer instructions
+    //=====================
+
+    /// ata of `owner` for `mint`, paid by the payer
+    pub fn create_ata_ix(&self, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &self.payer.pubkey(),
+            owner,
+            mint,
+            &spl_token::ID,
+        )
+    }
+
+    /// offer `id` of `deposit` mint_a for `expect` mint_b, the vault ata is created by the client
+    pub fn make_offer_ixs(
+        &self,
+        id: u64,
+        deposit: u64,
+        expect: u64,
+        expires_at: Option<i64>,
+    ) -> Vec<Instruction> {
+        self.make_offer_ixs_with(id, deposit, expect, expires_at, None, None)
+    }
+
+    pub fn make_offer_ixs_with(
+        &self,
+        id: u64,
+        deposit: u64,
+        expect: u64,
+        expires_at: Option<i64>,
+        allowed_takers: Option<AllowedTakers>,
+        pricing: Option<OraclePricing>,
+    ) -> Vec<Instruction> {
+        let maker = self.maker.pubkey();
+        let escrow = self.escrow_address(id);
+
+        vec![
+            self.create_ata_ix(&escrow, &self.mint_a),
+            instruction(
+                escrow::accounts::MakeOffer {
+                    maker,
+                    mint_a: self.mint_a,
+                    mint_b: self.mint_b,
+                    maker_offered_ata: get_associated_token_address(&maker, &self.mint_a),
+                    registry: self.registry_address(),
+                    escrow,
+                    vault: self.vault_address(id),
+                    token_program: spl_token::ID,
+                    associated_token_program: associated_token::ID,
+                    system_program: system_program::ID,
+                },
+                escrow::instruction::MakeOffer {
+                    deposit_amount: deposit,
+                    expect_amount: expect,
+                    expires_at,
+                    allowed_takers,
+                    pricing,
+                },
+            ),
+        ]
+    }
+
+    /// taker takes `amount` of offer `id`, at any price
+    pub fn take_offer_ix(&self, id: u64, amount: u64) -> Instruction {
+        self.take_offer_ix_with(id, amount, u64::MAX, self.taker.pubkey(), vec![], None)
+    }
+
+    pub fn take_offer_ix_with(
+        &self,
+        id: u64,
+        amount: u64,
+        max_payment: u64,
+        taker: Pubkey,
+        proof: Vec<[u8; 32]>,
+        price_feed: Option<Pubkey>,
+    ) -> Instruction {
+        let maker = self.maker.pubkey();
+
+        instruction(
+            escrow::accounts::TakeOffer {
+                taker,
+                taker_ata_a: get_associated_token_address(&taker, &self.mint_a),
+                taker_ata_b: get_associated_token_address(&taker, &self.mint_b),
+                maker,
+                mint_a: self.mint_a,
+                mint_b: self.mint_b,
+                escrow: self.escrow_address(id),
+                registry: self.registry_address(),
+                vault: self.vault_address(id),
+                maker_ata_b: get_associated_token_address(&maker, &self.mint_b),
+                price_feed,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+            },
+            escrow::instruction::TakeOffer {
+                amount,
+                max_payment,
+                proof,
+            },
+        )
+    }
+
+    pub fn cancel_offer_ix(&self, id: u64) -> Instruction {
+        let maker = self.maker.pubkey();
+
+        instruction(
+            escrow::accounts::CancelOffer {
+                maker,
+                escrow: self.escrow_address(id),
+                registry: self.registry_address(),
+                vault: self.vault_address(id),
+                maker_offered_ata: get_associated_token_address(&maker, &self.mint_a),
+                mint_a: self.mint_a,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+            },
+            escrow::instruction::CancelOffer {},
+        )
+    }
+
+    /// expiry crank, paid by the payer
+    pub fn refund_expired_ix(&self, id: u64) -> Instruction {
+        let maker = self.maker.pubkey();
+
+        instruction(
+            escrow::accounts::RefundExpired {
+                payer: self.payer.pubkey(),
+                maker,
+                escrow: self.escrow_address(id),
+                registry: self.registry_address(),
+                vault: self.vault_address(id),
+                maker_offered_ata: get_associated_token_address(&maker, &self.mint_a),
+                mint_a: self.mint_a,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+                token_program: spl_token::ID,
+            },
+            escrow::instruction::RefundExpired {},
+        )
+    }
+
+    pub fn get_offer_ix(&self, id: u64) -> Instruction {
+        instruction(
+            escrow::accounts::GetOffer {
+                maker_escrow: self.escrow_address(id),
+                vault: self.vault_address(id),
+                mint_a: self.mint_a,
+                mint_b: self.mint_b,
+                token_program: spl_token::ID,
+            },
+            escrow::instruction::GetOfferOf {
+                _maker_key: self.maker.pubkey(),
+                _id: id,
+            },
+        )
+    }
+
+    #[cfg(feature = "mock-oracle")]
+    pub fn update_feed_ix(authority: &Pubkey, price: i64, expo: i32) -> Instruction {
+        instruction(
+            escrow::accounts::UpdateMockFeed {
+                authority: *authority,
+                price_feed: Self::feed_address(authority),
+                system_program: system_program::ID,
+            },
+            escrow::instruction::UpdateMockFeed { price, expo },
+        )
+    }
+
+    //=====================
+    //    bundle instructions
+    //=====================
+
+    /// bundle `id` of the maker's `offered` assets, remaining accounts are derived from the assets
+    pub fn make_bundle_ix(
+        &self,
+        id: u64,
+        offered: Vec<BundleAsset>,
+        expected: Vec<BundleAsset>,
+    ) -> Instruction {
+        let (maker, bundle) = (self.maker.pubkey(), self.bundle_address(id));
+        let remaining: Vec<_> = offered
+            .iter()
+            .flat_map(|asset| {
+                [
+                    asset.mint,
+                    get_associated_token_address(&maker, &asset.mint),
+                    get_associated_token_address(&bundle, &asset.mint),
+                ]
+            })
+            .collect();
+
+        let mut ix = instruction(
+            escrow::accounts::MakeBundle {
+                maker,
+                registry: self.registry_address(),
+                bundle,
+                token_program: spl_token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            escrow::instruction::MakeBundle {
+                offered,
+                expected,
+                allowed_takers: None,
+            },
+        );
+        ix.accounts.extend(writable(&remaining));
+        ix
+    }
+
+    /// taker takes bundle `id`, offered assets first then expected ones
+    pub fn take_bundle_ix(
+        &self,
+        id: u64,
+        offered: &[BundleAsset],
+        expected: &[BundleAsset],
+    ) -> Instruction {
+        let (maker, taker, bundle) = (
+            self.maker.pubkey(),
+            self.taker.pubkey(),
+            self.bundle_address(id),
+        );
+        let offered = offered.iter().flat_map(|asset| {
+            [
+                asset.mint,
+                get_associated_token_address(&bundle, &asset.mint),
+                get_associated_token_address(&taker, &asset.mint),
+            ]
+        });
+        let expected = expected.iter().flat_map(|asset| {
+            [
+                asset.mint,
+                get_associated_token_address(&taker, &asset.mint),
+                get_associated_token_address(&maker, &asset.mint),
+            ]
+        });
+        let remaining: Vec<_> = offered.chain(expected).collect();
+
+        let mut ix = instruction(
+            escrow::accounts::TakeBundle {
+                taker,
+                maker,
+                bundle,
+                registry: self.registry_address(),
+                token_program: spl_token::ID,
+            },
+            escrow::instruction::TakeBundle { proof: vec![] },
+        );
+        ix.accounts.extend(writable(&remaining));
+        ix
+    }
+
+    pub fn cancel_bundle_ix(&self, id: u64, offered: &[BundleAsset]) -> Instruction {
+        let (maker, bundle) = (self.maker.pubkey(), self.bundle_address(id));
+        let remaining: Vec<_> = offered
+            .iter()
+            .flat_map(|asset| {
+                [
+                    asset.mint,
+                    get_associated_token_address(&bundle, &asset.mint),
+                    get_associated_token_address(&maker, &asset.mint),
+                ]
+            })
+            .collect();
+
+        let mut ix = instruction(
+            escrow::accounts::CancelBundle {
+                maker,
+                bundle,
+                registry: self.registry_address(),
+                token_program: spl_token::ID,
+            },
+            escrow::instruction::CancelBundle {},
+        );
+        ix.accounts.extend(writable(&remaining));
+        ix
+    }
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn writable(keys: &[Pubkey]) -> Vec<AccountMeta> {
+    keys.iter()
+        .map(|key| AccountMeta::new(*key, false))
+        .collect()
+}
+
+pub fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, mint)
+}
+
+/// asserts the transaction failed with `error` from the escrow program
+pub fn assert_error<T: std::fmt::Debug>(
+    result: std::result::Result<T, BanksClientError>,
+    error: EscrowErrors,
+) {
+    let code = anchor_lang::error::ERROR_CODE_OFFSET + error as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {error}"),
+        other => panic!("expected {error}, got {other:?}"),
+    }
+}
+
+/// asserts the transaction failed on an anchor account constraint
+pub fn assert_constraint_error<T: std::fmt::Debug>(
+    result: std::result::Result<T, BanksClientError>,
+    error: anchor_lang::error::ErrorCode,
+) {
+    let code = error as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {error}"),
+        other => panic!("expected {error}, got {other:?}"),
+    }
+}
+
+fn system_account(lamports: u64) -> Account {
+    Account::new(lamports, 0, &system_program::ID)
+}
+
+fn mint_account(decimals: u8, supply: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: None.into(),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+
+    rent_exempt(data, spl_token::ID)
+}
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    rent_exempt(data, spl_token::ID)
+}
+
+fn rent_exempt(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+use escrow::error::EscrowErrors;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn make_offer_rejects_past_expiry() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let now = env.now().await;
+
+    let result = env
+        .process(&env.make_offer_ixs(0, 100, 50, Some(now)), &[&maker])
+        .await;
+    assert_error(result, EscrowErrors::InvalidExpiry);
+}
+
+#[tokio::test]
+async fn expired_offer_is_refunded_by_anyone() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    let expires_at = env.now().await + 100;
+    env.process(&env.make_offer_ixs(0, 100, 50, Some(expires_at)), &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.take_offer_ix(0, 40)], &[&taker])
+        .await
+        .unwrap();
+
+    let result = env.process(&[env.refund_expired_ix(0)], &[]).await;
+    assert_error(result, EscrowErrors::OfferNotExpired);
+
+    env.warp(100).await;
+    let result = env.process(&[env.take_offer_ix(0, 10)], &[&taker]).await;
+    assert_error(result, EscrowErrors::OfferExpired);
+
+    let maker_before = env.lamports(maker.pubkey()).await;
+    let escrow_rent = env.lamports(env.escrow_address(0)).await;
+    let vault_rent = env.lamports(env.vault_address(0)).await;
+
+    // the crank is paid by the payer, the maker only gets tokens and rent back
+    env.process(&[env.refund_expired_ix(0)], &[]).await.unwrap();
+
+    let maker_ata = ata(&maker.pubkey(), &env.mint_a);
+    assert_eq!(env.token_amount(maker_ata).await, Some(MAKER_A - 40));
+    assert_eq!(
+        env.lamports(maker.pubkey()).await,
+        maker_before + escrow_rent + vault_rent
+    );
+    assert!(env.escrow(0).await.is_none());
+    assert!(env.registry().await.open_offers.is_empty());
+}
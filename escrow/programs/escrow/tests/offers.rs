@@ -0,0 +1,223 @@
+mod common;
+
+use anchor_lang::{error::ErrorCode, system_program, AnchorDeserialize};
+use anchor_spl::{associated_token, token::spl_token};
+use common::*;
+use escrow::{error::EscrowErrors, OfferView};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn make_offer_moves_tokens_into_vault() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+
+    env.process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    let escrow = env.escrow(0).await.unwrap();
+    assert_eq!(escrow.maker, maker.pubkey());
+    assert_eq!((escrow.mint_a, escrow.mint_b), (env.mint_a, env.mint_b));
+    assert_eq!((escrow.offered_amount, escrow.expected_amount), (100, 50));
+
+    let maker_ata = ata(&maker.pubkey(), &env.mint_a);
+    assert_eq!(env.token_amount(maker_ata).await, Some(MAKER_A - 100));
+    assert_eq!(env.token_amount(env.vault_address(0)).await, Some(100));
+}
+
+#[tokio::test]
+async fn partial_fills_round_up_in_makers_favour() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    env.process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    for amount in [0, 101] {
+        let result = env
+            .process(&[env.take_offer_ix(0, amount)], &[&taker])
+            .await;
+        assert_error(result, EscrowErrors::InvalidTakeAmount);
+    }
+
+    // 33 * 50 / 100 == 16.5, the maker gets 17
+    env.process(&[env.take_offer_ix(0, 33)], &[&taker])
+        .await
+        .unwrap();
+    let escrow = env.escrow(0).await.unwrap();
+    assert_eq!((escrow.offered_amount, escrow.expected_amount), (67, 33));
+
+    let maker_ata_b = ata(&maker.pubkey(), &env.mint_b);
+    assert_eq!(env.token_amount(maker_ata_b).await, Some(17));
+
+    // the rest of the offer pays exactly what's left of the expected amount
+    env.process(&[env.take_offer_ix(0, 67)], &[&taker])
+        .await
+        .unwrap();
+    assert_eq!(env.token_amount(maker_ata_b).await, Some(50));
+    assert_eq!(
+        env.token_amount(ata(&taker.pubkey(), &env.mint_a)).await,
+        Some(100)
+    );
+    assert!(env.account(env.vault_address(0)).await.is_none());
+    assert!(env.escrow(0).await.is_none());
+}
+
+#[tokio::test]
+async fn closing_fill_drains_the_whole_vault() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    env.process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    // tokens sent to the vault outside of the offer must not block its close
+    let (mint_a, vault) = (env.mint_a, env.vault_address(0));
+    env.send_tokens(&maker, mint_a, vault, 5).await;
+
+    env.process(&[env.take_offer_ix(0, 100)], &[&taker])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        env.token_amount(ata(&taker.pubkey(), &env.mint_a)).await,
+        Some(105)
+    );
+    assert!(env.account(vault).await.is_none());
+    assert!(env.escrow(0).await.is_none());
+}
+
+#[tokio::test]
+async fn take_offer_rejects_other_expected_mint() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    env.process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    // taker tries to pay in mint_d instead of the expected mint_b
+    let ix = instruction(
+        escrow::accounts::TakeOffer {
+            taker: taker.pubkey(),
+            taker_ata_a: ata(&taker.pubkey(), &env.mint_a),
+            taker_ata_b: ata(&taker.pubkey(), &env.mint_d),
+            maker: maker.pubkey(),
+            mint_a: env.mint_a,
+            mint_b: env.mint_d,
+            escrow: env.escrow_address(0),
+            registry: env.registry_address(),
+            vault: env.vault_address(0),
+            maker_ata_b: ata(&maker.pubkey(), &env.mint_d),
+            price_feed: None,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+        },
+        escrow::instruction::TakeOffer {
+            amount: 1,
+            max_payment: u64::MAX,
+            proof: vec![],
+        },
+    );
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_constraint_error(result, ErrorCode::ConstraintHasOne);
+}
+
+#[tokio::test]
+async fn cancel_offer_returns_tokens() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    env.process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    env.process(&[env.cancel_offer_ix(0)], &[&maker])
+        .await
+        .unwrap();
+
+    let maker_ata = ata(&maker.pubkey(), &env.mint_a);
+    assert_eq!(env.token_amount(maker_ata).await, Some(MAKER_A));
+    assert!(env.account(env.vault_address(0)).await.is_none());
+    assert!(env.escrow(0).await.is_none());
+    assert!(env.registry().await.open_offers.is_empty());
+}
+
+#[tokio::test]
+async fn registry_tracks_open_offers() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    for id in 0..3 {
+        env.process(&env.make_offer_ixs(id, 100, 50, None), &[&maker])
+            .await
+            .unwrap();
+    }
+
+    // cancelled and fully taken offers leave the registry, ids are never reused
+    env.process(&[env.cancel_offer_ix(1)], &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.take_offer_ix(2, 100)], &[&taker])
+        .await
+        .unwrap();
+    env.process(&env.make_offer_ixs(3, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    let registry = env.registry().await;
+    assert_eq!(registry.maker, maker.pubkey());
+    assert_eq!(registry.next_id, 4);
+    assert_eq!(registry.open_offers, vec![0, 3]);
+
+    // escrow seeds must use the registry counter
+    let result = env
+        .process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await;
+    assert_constraint_error(result, ErrorCode::ConstraintSeeds);
+}
+
+#[tokio::test]
+async fn get_offer_of_returns_view() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    env.process(&env.make_offer_ixs(0, 100, 50, Some(i64::MAX)), &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.take_offer_ix(0, 40)], &[&taker])
+        .await
+        .unwrap();
+
+    // read only, so it can be simulated without any writable lock
+    let ix = env.get_offer_ix(0);
+    assert!(ix.accounts.iter().all(|meta| !meta.is_writable));
+
+    let data = env.simulate(&[ix]).await;
+    let view = OfferView::deserialize(&mut data.as_slice()).unwrap();
+    assert_eq!((view.maker, view.id), (maker.pubkey(), 0));
+    assert_eq!((view.offered_amount, view.expected_amount), (60, 30));
+    assert_eq!(view.expires_at, Some(i64::MAX));
+    assert_eq!(view.vault_balance, 60);
+    assert_eq!(
+        (view.mint_a_decimals, view.mint_b_decimals),
+        (DECIMALS_A, DECIMALS_B)
+    );
+}
+
+#[tokio::test]
+async fn take_offer_respects_max_payment() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    env.process(&env.make_offer_ixs(0, 100, 50, None), &[&maker])
+        .await
+        .unwrap();
+
+    // 40 of the offer costs 20
+    let t = taker.pubkey();
+    let ix = env.take_offer_ix_with(0, 40, 19, t, vec![], None);
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::PaymentAboveMax);
+
+    let ix = env.take_offer_ix_with(0, 40, 20, t, vec![], None);
+    env.process(&[ix], &[&taker]).await.unwrap();
+    let maker_ata_b = ata(&maker.pubkey(), &env.mint_b);
+    assert_eq!(env.token_amount(maker_ata_b).await, Some(20));
+}
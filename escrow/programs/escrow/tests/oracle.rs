@@ -0,0 +1,208 @@
+mod common;
+
+use common::*;
+use escrow::{error::EscrowErrors, OraclePricing, VerificationLevel};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// pyth price update publishing 1 mint_a == 2.5 mint_b, confident to 0.4%
+async fn feed(env: &mut Env) -> Pubkey {
+    let feed = Pubkey::new_unique();
+    env.set_price_update(feed, 250, 1, -2, VerificationLevel::Full)
+        .await;
+    feed
+}
+
+/// quoted 1% above the feed, refused once the price is older than a minute or unsure by more than 1%
+fn pricing(feed: Pubkey) -> OraclePricing {
+    OraclePricing {
+        feed,
+        spread_bps: 100,
+        max_staleness: 60,
+        max_confidence_bps: 100,
+    }
+}
+
+/// offer 0 of 100 mint_a, quoted from a fresh feed
+async fn priced_offer(env: &mut Env) -> (Keypair, Pubkey) {
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    let feed = feed(env).await;
+    let ixs = env.make_offer_ixs_with(0, 100, 0, None, None, Some(pricing(feed)));
+    env.process(&ixs, &[&maker]).await.unwrap();
+    (taker, feed)
+}
+
+#[tokio::test]
+async fn make_offer_rejects_invalid_pricing() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let pricing = pricing(feed(&mut env).await);
+
+    let invalid = [
+        (5, pricing), // fixed expected amount on top of the feed
+        (
+            0,
+            OraclePricing {
+                spread_bps: -10_000,
+                ..pricing
+            },
+        ),
+        (
+            0,
+            OraclePricing {
+                max_staleness: 0,
+                ..pricing
+            },
+        ),
+        (
+            0,
+            OraclePricing {
+                max_confidence_bps: 0,
+                ..pricing
+            },
+        ),
+    ];
+    for (expect, pricing) in invalid {
+        let ixs = env.make_offer_ixs_with(0, 100, expect, None, None, Some(pricing));
+        let result = env.process(&ixs, &[&maker]).await;
+        assert_error(result, EscrowErrors::InvalidPricing);
+    }
+}
+
+#[tokio::test]
+async fn take_offer_quotes_from_the_feed() {
+    let mut env = Env::new().await;
+    let maker = env.maker.insecure_clone();
+    let (taker, feed) = priced_offer(&mut env).await;
+
+    let t = taker.pubkey();
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, t, vec![], None);
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::MissingPriceFeed);
+
+    // any other price update is refused, even a valid one
+    let other = Pubkey::new_unique();
+    env.set_price_update(other, 1, 0, 0, VerificationLevel::Full)
+        .await;
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, t, vec![], Some(other));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::PriceFeedMismatch);
+
+    // 10 raw mint_a (6 decimals) * 2.5 * 1.01 == 25_250 raw mint_b (9 decimals)
+    let ix = env.take_offer_ix_with(0, 10, 25_249, t, vec![], Some(feed));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::PaymentAboveMax);
+    let ix = env.take_offer_ix_with(0, 10, 25_250, t, vec![], Some(feed));
+    env.process(&[ix], &[&taker]).await.unwrap();
+
+    let maker_ata_b = ata(&maker.pubkey(), &env.mint_b);
+    assert_eq!(env.token_amount(maker_ata_b).await, Some(25_250));
+    let escrow = env.escrow(0).await.unwrap();
+    assert_eq!((escrow.offered_amount, escrow.expected_amount), (90, 0));
+
+    // market moves to 3.0, the next take follows it
+    env.set_price_update(feed, 3, 0, 0, VerificationLevel::Full)
+        .await;
+    let ix = env.take_offer_ix_with(0, 1, u64::MAX, t, vec![], Some(feed));
+    env.process(&[ix], &[&taker]).await.unwrap();
+    assert_eq!(env.token_amount(maker_ata_b).await, Some(25_250 + 3_030));
+}
+
+#[tokio::test]
+async fn take_offer_rejects_stale_price() {
+    let mut env = Env::new().await;
+    let (taker, feed) = priced_offer(&mut env).await;
+
+    env.warp(61).await;
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::StalePrice);
+}
+
+#[tokio::test]
+async fn take_offer_rejects_non_positive_price() {
+    let mut env = Env::new().await;
+    let (taker, feed) = priced_offer(&mut env).await;
+
+    for price in [0, -250] {
+        env.set_price_update(feed, price, 0, -2, VerificationLevel::Full)
+            .await;
+        let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+        let result = env.process(&[ix], &[&taker]).await;
+        assert_error(result, EscrowErrors::InvalidPrice);
+    }
+}
+
+#[tokio::test]
+async fn take_offer_rejects_uncertain_price() {
+    let mut env = Env::new().await;
+    let (taker, feed) = priced_offer(&mut env).await;
+
+    // 2.5 +- 0.03 is 1.2% either way, past the offer's 1%
+    env.set_price_update(feed, 250, 3, -2, VerificationLevel::Full)
+        .await;
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::PriceUncertain);
+
+    // exactly 1% is still fine
+    env.set_price_update(feed, 300, 3, -2, VerificationLevel::Full)
+        .await;
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+    env.process(&[ix], &[&taker]).await.unwrap();
+}
+
+#[tokio::test]
+async fn take_offer_rejects_partially_verified_price() {
+    let mut env = Env::new().await;
+    let (taker, feed) = priced_offer(&mut env).await;
+
+    let partial = VerificationLevel::Partial { num_signatures: 5 };
+    env.set_price_update(feed, 250, 1, -2, partial).await;
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::UnverifiedPrice);
+}
+
+#[tokio::test]
+async fn take_offer_rejects_foreign_feed_account() {
+    let mut env = Env::new().await;
+    let (taker, feed) = priced_offer(&mut env).await;
+
+    // a valid looking price update owned by anyone but the pyth receiver
+    let mut account = env.account(feed).await.unwrap();
+    account.owner = Pubkey::new_unique();
+    env.set_account(feed, account.clone());
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::OracleNotSupported);
+
+    // receiver owned, but not a price update
+    account.owner = escrow::PYTH_RECEIVER_ID;
+    account.data[0] ^= 1;
+    env.set_account(feed, account);
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![], Some(feed));
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::OracleNotSupported);
+}
+
+#[cfg(feature = "mock-oracle")]
+#[tokio::test]
+async fn take_offer_quotes_from_mock_feed() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.maker.insecure_clone(), env.taker.insecure_clone());
+    let authority = env.other.insecure_clone();
+    let publish = Env::update_feed_ix(&authority.pubkey(), 250, -2);
+    env.process(&[publish], &[&authority]).await.unwrap();
+
+    let feed = Env::feed_address(&authority.pubkey());
+    let ixs = env.make_offer_ixs_with(0, 100, 0, None, None, Some(pricing(feed)));
+    env.process(&ixs, &[&maker]).await.unwrap();
+
+    let ix = env.take_offer_ix_with(0, 10, 25_250, taker.pubkey(), vec![], Some(feed));
+    env.process(&[ix], &[&taker]).await.unwrap();
+    let maker_ata_b = ata(&maker.pubkey(), &env.mint_b);
+    assert_eq!(env.token_amount(maker_ata_b).await, Some(25_250));
+}
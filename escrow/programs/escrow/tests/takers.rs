@@ -0,0 +1,78 @@
+mod common;
+
+use anchor_lang::{prelude::Pubkey, solana_program::hash::hashv};
+use common::*;
+use escrow::{error::EscrowErrors, AllowedTakers};
+use solana_sdk::signature::Signer;
+
+/// merkle node of two children, sorted like the program does
+fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[&a, &b]).to_bytes()
+    } else {
+        hashv(&[&b, &a]).to_bytes()
+    }
+}
+
+#[tokio::test]
+async fn designated_taker_only() {
+    let mut env = Env::new().await;
+    let (maker, taker, other) = (
+        env.maker.insecure_clone(),
+        env.taker.insecure_clone(),
+        env.other.insecure_clone(),
+    );
+    let allowed = Some(AllowedTakers::Taker(taker.pubkey()));
+    env.process(
+        &env.make_offer_ixs_with(0, 100, 50, None, allowed, None),
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, other.pubkey(), vec![], None);
+    let result = env.process(&[ix], &[&other]).await;
+    assert_error(result, EscrowErrors::TakerNotAllowed);
+
+    env.process(&[env.take_offer_ix(0, 10)], &[&taker])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn merkle_listed_takers_only() {
+    let mut env = Env::new().await;
+    let (maker, taker, other) = (
+        env.maker.insecure_clone(),
+        env.taker.insecure_clone(),
+        env.other.insecure_clone(),
+    );
+
+    let leaves: Vec<[u8; 32]> = [taker.pubkey(), Pubkey::new_unique(), Pubkey::new_unique()]
+        .iter()
+        .map(|key| hashv(&[key.as_ref()]).to_bytes())
+        .collect();
+    let root = node(node(leaves[0], leaves[1]), leaves[2]);
+    let allowed = Some(AllowedTakers::MerkleRoot(root));
+    env.process(
+        &env.make_offer_ixs_with(0, 100, 50, None, allowed, None),
+        &[&maker],
+    )
+    .await
+    .unwrap();
+
+    // someone outside the tree, with a valid proof of another leaf
+    let proof = vec![leaves[1], leaves[2]];
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, other.pubkey(), proof.clone(), None);
+    let result = env.process(&[ix], &[&other]).await;
+    assert_error(result, EscrowErrors::TakerNotAllowed);
+
+    // listed taker with an incomplete proof
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), vec![leaves[2]], None);
+    let result = env.process(&[ix], &[&taker]).await;
+    assert_error(result, EscrowErrors::TakerNotAllowed);
+
+    let ix = env.take_offer_ix_with(0, 10, u64::MAX, taker.pubkey(), proof, None);
+    env.process(&[ix], &[&taker]).await.unwrap();
+    assert_eq!(env.escrow(0).await.unwrap().offered_amount, 90);
+}
@@ -0,0 +1,10 @@
+//! Off-chain indexer for marketplace events.
+//!
+//! Decodes the `#[event]`s emitted by the marketplace program from transaction logs
+//! and stores them in a local SQLite database, queryable by collection, seller and time.
+
+pub mod parser;
+pub mod store;
+
+pub use parser::*;
+pub use store::*;
@@ -0,0 +1,89 @@
+//! Usage:
+//!
+//! ```text
+//! marketplace-indexer <db> ingest < transactions.jsonl
+//! marketplace-indexer <db> query [--kind <kind>] [--collection <pubkey>] [--seller <pubkey>] [--from <unix>] [--to <unix>]
+//! ```
+//!
+//! `ingest` reads one transaction per line as `{"signature", "slot", "err", "logs"}`,
+//! the shape of a `logsSubscribe` notification with its context slot, and skips failed ones.
+//! `query` prints matching events as json lines.
+
+use std::{
+    error::Error,
+    io::{stdin, BufRead},
+};
+
+use marketplace_indexer::{parse_logs, EventFilter, Store};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Transaction {
+    signature: String,
+    slot: u64,
+    #[serde(default)]
+    err: Option<serde_json::Value>,
+    logs: Vec<String>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (Some(db), Some(command)) = (args.first(), args.get(1)) else {
+        return Err("usage: marketplace-indexer <db> <ingest|query> [filters]".into());
+    };
+
+    let mut store = Store::open(db)?;
+
+    match command.as_str() {
+        "ingest" => ingest(&mut store),
+        "query" => query(&store, &args[2..]),
+        _ => Err(format!("unknown command `{command}`").into()),
+    }
+}
+
+fn ingest(store: &mut Store) -> Result<(), Box<dyn Error>> {
+    let mut inserted = 0;
+
+    for line in stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let transaction: Transaction = serde_json::from_str(&line)?;
+        if transaction.err.is_some_and(|err| !err.is_null()) {
+            continue;
+        }
+
+        let events = parse_logs(&transaction.logs);
+        inserted += store.insert(&transaction.signature, transaction.slot, &events)?;
+    }
+
+    eprintln!("Indexed {inserted} events");
+    Ok(())
+}
+
+fn query(store: &Store, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut filter = EventFilter::default();
+
+    for pair in args.chunks(2) {
+        let [flag, value] = pair else {
+            return Err(format!("missing value for `{}`", pair[0]).into());
+        };
+
+        match flag.as_str() {
+            "--kind" => filter.kind = Some(value.clone()),
+            "--collection" => filter.collection = Some(value.clone()),
+            "--seller" => filter.seller = Some(value.clone()),
+            "--from" => filter.from = Some(value.parse()?),
+            "--to" => filter.to = Some(value.parse()?),
+            _ => return Err(format!("unknown filter `{flag}`").into()),
+        }
+    }
+
+    for row in store.query(&filter)? {
+        println!("{}", serde_json::to_string(&row)?);
+    }
+
+    Ok(())
+}
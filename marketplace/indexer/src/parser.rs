@@ -0,0 +1,127 @@
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use marketplace::{
+    AuctionBidPlaced, AuctionClosed, AuctionCreated, BidCancelled, BidPlaced, Delisted, FeeApplied,
+    FeeUpdated, FreezeStateChanged, Listed, ListingUpdated, Purchased, Reclaimed, RentalDelisted,
+    RentalListed, Rented, TradeAccepted, TradeCancelled, TradeDeposited, TradeProposed,
+    TradeWithdrawn,
+};
+
+pub enum MarketplaceEvent {
+    Listed(Listed),
+    Delisted(Delisted),
+    ListingUpdated(ListingUpdated),
+    Purchased(Purchased),
+    BidPlaced(BidPlaced),
+    BidCancelled(BidCancelled),
+    AuctionCreated(AuctionCreated),
+    AuctionBidPlaced(AuctionBidPlaced),
+    AuctionClosed(AuctionClosed),
+    RentalListed(RentalListed),
+    RentalDelisted(RentalDelisted),
+    Rented(Rented),
+    Reclaimed(Reclaimed),
+    TradeProposed(TradeProposed),
+    TradeCancelled(TradeCancelled),
+    TradeDeposited(TradeDeposited),
+    TradeWithdrawn(TradeWithdrawn),
+    TradeAccepted(TradeAccepted),
+    FeeUpdated(FeeUpdated),
+    FeeApplied(FeeApplied),
+    FreezeStateChanged(FreezeStateChanged),
+}
+
+impl MarketplaceEvent {
+    /// decodes an anchor event, `data` being the discriminator followed by the borsh serialized event
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        fn decode<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> Option<T> {
+            let mut data = data.strip_prefix(T::DISCRIMINATOR)?;
+            T::deserialize(&mut data).ok()
+        }
+
+        decode(data)
+            .map(Self::Listed)
+            .or_else(|| decode(data).map(Self::Delisted))
+            .or_else(|| decode(data).map(Self::ListingUpdated))
+            .or_else(|| decode(data).map(Self::Purchased))
+            .or_else(|| decode(data).map(Self::BidPlaced))
+            .or_else(|| decode(data).map(Self::BidCancelled))
+            .or_else(|| decode(data).map(Self::AuctionCreated))
+            .or_else(|| decode(data).map(Self::AuctionBidPlaced))
+            .or_else(|| decode(data).map(Self::AuctionClosed))
+            .or_else(|| decode(data).map(Self::RentalListed))
+            .or_else(|| decode(data).map(Self::RentalDelisted))
+            .or_else(|| decode(data).map(Self::Rented))
+            .or_else(|| decode(data).map(Self::Reclaimed))
+            .or_else(|| decode(data).map(Self::TradeProposed))
+            .or_else(|| decode(data).map(Self::TradeCancelled))
+            .or_else(|| decode(data).map(Self::TradeDeposited))
+            .or_else(|| decode(data).map(Self::TradeWithdrawn))
+            .or_else(|| decode(data).map(Self::TradeAccepted))
+            .or_else(|| decode(data).map(Self::FeeUpdated))
+            .or_else(|| decode(data).map(Self::FeeApplied))
+            .or_else(|| decode(data).map(Self::FreezeStateChanged))
+    }
+}
+
+/// Extracts marketplace events from the logs of a successful transaction, in emission order.
+///
+/// Only `Program data:` lines logged while the marketplace program is the innermost
+/// invoked program are decoded, so events of other programs are never picked up.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<MarketplaceEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let log = log.as_ref();
+
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if stack.last() != Some(&marketplace::ID) {
+                continue;
+            }
+            // `sol_log_data` logs one base64 string per slice, `emit!` logs a single one
+            let Some(event) = data
+                .split_whitespace()
+                .next()
+                .and_then(|data| STANDARD.decode(data).ok())
+                .and_then(|data| MarketplaceEvent::decode(&data))
+            else {
+                continue;
+            };
+            events.push(event);
+        } else {
+            match Frame::parse(log) {
+                Some(Frame::Invoke(program)) => stack.push(program),
+                Some(Frame::Exit) => {
+                    stack.pop();
+                }
+                None => {}
+            }
+        }
+    }
+
+    events
+}
+
+/// runtime lines opening or closing a program invocation
+enum Frame {
+    Invoke(Pubkey),
+    Exit,
+}
+
+impl Frame {
+    /// `Program <id> invoke [<depth>]`, `Program <id> success` or `Program <id> failed: <reason>`,
+    /// anything else, like a program's own `Program log: success`, is not a frame
+    fn parse(log: &str) -> Option<Self> {
+        let (program, status) = log.strip_prefix("Program ")?.split_once(' ')?;
+        let program = program.parse::<Pubkey>().ok()?;
+
+        if status == "success" || status.starts_with("failed: ") {
+            return Some(Self::Exit);
+        }
+
+        let depth = status.strip_prefix("invoke [")?.strip_suffix(']')?;
+        depth.parse::<u8>().ok()?;
+        Some(Self::Invoke(program))
+    }
+}
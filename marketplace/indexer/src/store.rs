@@ -0,0 +1,366 @@
+use std::path::Path;
+
+use anchor_lang::prelude::Pubkey;
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+
+use crate::MarketplaceEvent;
+
+/// One indexed event, pubkeys are base58 strings and fields a kind doesn't carry are None.
+///
+/// Bidders are stored as `buyer`, the target of a bid as `mint` or `collection`.
+/// Rental owners and trade makers are stored as `seller`, renters and trade takers as `buyer`.
+/// Trades are told apart by `trade_id` together with their maker, the traded mints aren't stored.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRow {
+    pub signature: String,
+    pub slot: u64,
+    // listed, updated, delisted, expired, purchased, bid_placed, bid_cancelled, auction_created,
+    // auction_bid, auction_closed, rental_listed, rental_delisted, rented, reclaimed, trade_proposed,
+    // trade_cancelled, trade_deposited, trade_withdrawn, trade_accepted, fee_updated, fee_applied,
+    // frozen, thawed
+    pub kind: String,
+    pub seller: Option<String>,
+    pub buyer: Option<String>,
+    pub mint: Option<String>,
+    pub collection: Option<String>,
+    pub price: Option<u64>, // sol of a trade, per day price of a rental listing
+    pub fee_lamports: Option<u64>, // fee paid on a sale or rental
+    pub fee_bps: Option<u16>, // fee rate of a listing, auction or fee change
+    pub royalties: Option<u64>, // always None, the program doesn't pay creator royalties yet
+    pub effective_at: Option<i64>, // when a pending fee becomes applicable, or a rental ends
+    pub trade_id: Option<u64>,
+    pub timestamp: i64,
+}
+
+/// Query filter, None fields match everything and the time range is inclusive.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub kind: Option<String>,
+    pub collection: Option<String>,
+    pub seller: Option<String>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                signature    TEXT    NOT NULL,
+                slot         INTEGER NOT NULL,
+                idx          INTEGER NOT NULL,
+                kind         TEXT    NOT NULL,
+                seller       TEXT,
+                buyer        TEXT,
+                mint         TEXT,
+                collection   TEXT,
+                price        INTEGER,
+                fee_lamports INTEGER,
+                fee_bps      INTEGER,
+                royalties    INTEGER,
+                effective_at INTEGER,
+                trade_id     INTEGER,
+                timestamp    INTEGER NOT NULL,
+                PRIMARY KEY (signature, idx)
+            );
+            CREATE INDEX IF NOT EXISTS events_collection ON events (collection, timestamp);
+            CREATE INDEX IF NOT EXISTS events_seller ON events (seller, timestamp);
+            CREATE INDEX IF NOT EXISTS events_timestamp ON events (timestamp);",
+        )?;
+
+        Ok(Self { conn })
+    }
+
+    /// Stores the events of one transaction, ingesting the same transaction twice is a no-op.
+    pub fn insert(
+        &mut self,
+        signature: &str,
+        slot: u64,
+        events: &[MarketplaceEvent],
+    ) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut inserted = 0;
+
+        for (idx, event) in events.iter().enumerate() {
+            let row = EventRow::new(signature, slot, event);
+            inserted += tx.execute(
+                "INSERT OR IGNORE INTO events (
+                    signature, slot, idx, kind, seller, buyer, mint, collection,
+                    price, fee_lamports, fee_bps, royalties, effective_at, trade_id, timestamp
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                params![
+                    row.signature,
+                    row.slot as i64,
+                    idx as i64,
+                    row.kind,
+                    row.seller,
+                    row.buyer,
+                    row.mint,
+                    row.collection,
+                    row.price.map(|price| price as i64),
+                    row.fee_lamports.map(|fee| fee as i64),
+                    row.fee_bps,
+                    row.royalties.map(|royalties| royalties as i64),
+                    row.effective_at,
+                    row.trade_id.map(|id| id as i64),
+                    row.timestamp,
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Events matching `filter`, oldest first.
+    pub fn query(&self, filter: &EventFilter) -> rusqlite::Result<Vec<EventRow>> {
+        let mut statement = self.conn.prepare(
+            "SELECT signature, slot, kind, seller, buyer, mint, collection,
+                    price, fee_lamports, fee_bps, royalties, effective_at, trade_id, timestamp
+             FROM events
+             WHERE (?1 IS NULL OR kind = ?1)
+               AND (?2 IS NULL OR collection = ?2)
+               AND (?3 IS NULL OR seller = ?3)
+               AND (?4 IS NULL OR timestamp >= ?4)
+               AND (?5 IS NULL OR timestamp <= ?5)
+             ORDER BY timestamp, slot, signature, idx",
+        )?;
+
+        let rows = statement.query_map(
+            params![
+                filter.kind,
+                filter.collection,
+                filter.seller,
+                filter.from,
+                filter.to
+            ],
+            EventRow::from_row,
+        )?;
+        rows.collect()
+    }
+}
+
+impl EventRow {
+    pub fn new(signature: &str, slot: u64, event: &MarketplaceEvent) -> Self {
+        let mut row = EventRow {
+            signature: signature.to_string(),
+            slot,
+            kind: String::new(),
+            seller: None,
+            buyer: None,
+            mint: None,
+            collection: None,
+            price: None,
+            fee_lamports: None,
+            fee_bps: None,
+            royalties: None,
+            effective_at: None,
+            trade_id: None,
+            timestamp: 0,
+        };
+
+        match event {
+            MarketplaceEvent::Listed(event) => {
+                row.kind = "listed".to_string();
+                row.seller = Some(event.seller.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.price);
+                row.fee_bps = Some(event.fee);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::Delisted(event) => {
+                row.kind = if event.expired { "expired" } else { "delisted" }.to_string();
+                row.seller = Some(event.seller.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::ListingUpdated(event) => {
+                row.kind = "updated".to_string();
+                row.seller = Some(event.seller.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.price);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::Purchased(event) => {
+                row.kind = "purchased".to_string();
+                row.seller = Some(event.seller.to_string());
+                row.buyer = Some(event.buyer.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.price);
+                row.fee_lamports = Some(event.fee);
+                row.royalties = event.royalties;
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::BidPlaced(event) => {
+                row.kind = "bid_placed".to_string();
+                row.set_bid_target(&event.bidder, &event.target, event.collection);
+                row.price = Some(event.price);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::BidCancelled(event) => {
+                row.kind = "bid_cancelled".to_string();
+                row.set_bid_target(&event.bidder, &event.target, event.collection);
+                row.price = Some(event.price);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::AuctionCreated(event) => {
+                row.kind = "auction_created".to_string();
+                row.seller = Some(event.seller.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.reserve_price);
+                row.fee_bps = Some(event.fee);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::AuctionBidPlaced(event) => {
+                row.kind = "auction_bid".to_string();
+                row.seller = Some(event.seller.to_string());
+                row.buyer = Some(event.bidder.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.amount);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::AuctionClosed(event) => {
+                row.kind = "auction_closed".to_string();
+                row.seller = Some(event.seller.to_string());
+                row.buyer = event.refunded_bidder.map(|bidder| bidder.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.refund);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::RentalListed(event) => {
+                row.kind = "rental_listed".to_string();
+                row.seller = Some(event.owner.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.price_per_day);
+                row.fee_bps = Some(event.fee);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::RentalDelisted(event) => {
+                row.kind = "rental_delisted".to_string();
+                row.seller = Some(event.owner.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::Rented(event) => {
+                row.kind = "rented".to_string();
+                row.seller = Some(event.owner.to_string());
+                row.buyer = Some(event.renter.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.price = Some(event.price);
+                row.fee_lamports = Some(event.fee);
+                row.effective_at = Some(event.rented_until);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::Reclaimed(event) => {
+                row.kind = "reclaimed".to_string();
+                row.seller = Some(event.owner.to_string());
+                row.buyer = Some(event.renter.to_string());
+                row.mint = Some(event.mint.to_string());
+                row.collection = Some(event.collection.to_string());
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::TradeProposed(event) => {
+                row.kind = "trade_proposed".to_string();
+                row.set_trade(&event.maker, &event.taker, event.id);
+                row.price = Some(event.lamports);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::TradeCancelled(event) => {
+                row.kind = "trade_cancelled".to_string();
+                row.set_trade(&event.maker, &event.taker, event.id);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::TradeDeposited(event) => {
+                row.kind = "trade_deposited".to_string();
+                row.set_trade(&event.maker, &event.taker, event.id);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::TradeWithdrawn(event) => {
+                row.kind = "trade_withdrawn".to_string();
+                row.set_trade(&event.maker, &event.taker, event.id);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::TradeAccepted(event) => {
+                row.kind = "trade_accepted".to_string();
+                row.set_trade(&event.maker, &event.taker, event.id);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::FeeUpdated(event) => {
+                row.kind = "fee_updated".to_string();
+                row.fee_bps = Some(event.new_fee);
+                row.effective_at = Some(event.effective_at);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::FeeApplied(event) => {
+                row.kind = "fee_applied".to_string();
+                row.fee_bps = Some(event.fee);
+                row.timestamp = event.timestamp;
+            }
+            MarketplaceEvent::FreezeStateChanged(event) => {
+                row.kind = if event.frozen { "frozen" } else { "thawed" }.to_string();
+                row.timestamp = event.timestamp;
+            }
+        }
+
+        row
+    }
+
+    fn set_bid_target(&mut self, bidder: &Pubkey, target: &Pubkey, collection: bool) {
+        self.buyer = Some(bidder.to_string());
+        if collection {
+            self.collection = Some(target.to_string());
+        } else {
+            self.mint = Some(target.to_string());
+        }
+    }
+
+    fn set_trade(&mut self, maker: &Pubkey, taker: &Pubkey, id: u64) {
+        self.seller = Some(maker.to_string());
+        self.buyer = Some(taker.to_string());
+        self.trade_id = Some(id);
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(EventRow {
+            signature: row.get(0)?,
+            slot: row.get::<_, i64>(1)? as u64,
+            kind: row.get(2)?,
+            seller: row.get(3)?,
+            buyer: row.get(4)?,
+            mint: row.get(5)?,
+            collection: row.get(6)?,
+            price: row.get::<_, Option<i64>>(7)?.map(|price| price as u64),
+            fee_lamports: row.get::<_, Option<i64>>(8)?.map(|fee| fee as u64),
+            fee_bps: row.get(9)?,
+            royalties: row
+                .get::<_, Option<i64>>(10)?
+                .map(|royalties| royalties as u64),
+            effective_at: row.get(11)?,
+            trade_id: row.get::<_, Option<i64>>(12)?.map(|id| id as u64),
+            timestamp: row.get(13)?,
+        })
+    }
+}
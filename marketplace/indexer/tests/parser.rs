@@ -0,0 +1,146 @@
+use anchor_lang::{prelude::Pubkey, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use marketplace::{BidPlaced, Listed, Purchased};
+use marketplace_indexer::{parse_logs, MarketplaceEvent};
+
+fn listed(price: u64) -> Listed {
+    Listed {
+        seller: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        collection: Pubkey::new_unique(),
+        price,
+        fee: 25,
+        expires_at: None,
+        allowed_buyer: None,
+        timestamp: 1_700_000_000,
+    }
+}
+
+fn data_log(event: &impl Event) -> String {
+    format!("Program data: {}", STANDARD.encode(event.data()))
+}
+
+fn invoke(program: &Pubkey, depth: u8) -> String {
+    format!("Program {program} invoke [{depth}]")
+}
+
+fn success(program: &Pubkey) -> String {
+    format!("Program {program} success")
+}
+
+fn prices(events: &[MarketplaceEvent]) -> Vec<u64> {
+    events
+        .iter()
+        .map(|event| match event {
+            MarketplaceEvent::Listed(event) => event.price,
+            MarketplaceEvent::Purchased(event) => event.price,
+            MarketplaceEvent::BidPlaced(event) => event.price,
+            _ => panic!("unexpected event"),
+        })
+        .collect()
+}
+
+#[test]
+fn decodes_events_in_emission_order() {
+    let bid = BidPlaced {
+        bidder: Pubkey::new_unique(),
+        target: Pubkey::new_unique(),
+        collection: true,
+        price: 2,
+        timestamp: 1_700_000_000,
+    };
+    let logs = [
+        invoke(&marketplace::ID, 1),
+        "Program log: Instruction: ListNft".to_string(),
+        data_log(&listed(1)),
+        data_log(&bid),
+        success(&marketplace::ID),
+    ];
+
+    let events = parse_logs(&logs);
+    assert_eq!(prices(&events), [1, 2]);
+    assert!(matches!(&events[1], MarketplaceEvent::BidPlaced(event) if event.collection));
+}
+
+#[test]
+fn ignores_data_logged_by_nested_programs() {
+    let other = Pubkey::new_unique();
+    let purchased = Purchased {
+        buyer: Pubkey::new_unique(),
+        seller: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        collection: Pubkey::new_unique(),
+        price: 3,
+        fee: 0,
+        royalties: None,
+        timestamp: 1_700_000_000,
+    };
+    let logs = [
+        invoke(&marketplace::ID, 1),
+        data_log(&listed(1)),
+        // same bytes logged from a cpi'd program must not be attributed to the marketplace
+        invoke(&other, 2),
+        data_log(&listed(2)),
+        invoke(&marketplace::ID, 3),
+        data_log(&listed(4)),
+        success(&marketplace::ID),
+        data_log(&listed(5)),
+        success(&other),
+        data_log(&purchased),
+        success(&marketplace::ID),
+        // outside of any invocation
+        data_log(&listed(6)),
+    ];
+
+    assert_eq!(prices(&parse_logs(&logs)), [1, 4, 3]);
+}
+
+#[test]
+fn failed_frames_are_popped() {
+    let other = Pubkey::new_unique();
+    let logs = [
+        invoke(&marketplace::ID, 1),
+        invoke(&other, 2),
+        format!("Program {other} failed: custom program error: 0x1"),
+        data_log(&listed(1)),
+        success(&marketplace::ID),
+    ];
+
+    assert_eq!(prices(&parse_logs(&logs)), [1]);
+}
+
+#[test]
+fn skips_unknown_and_malformed_data() {
+    let logs = [
+        invoke(&marketplace::ID, 1),
+        format!("Program data: {}", STANDARD.encode([7u8; 16])),
+        "Program data: not base64!".to_string(),
+        // known discriminator with a truncated body
+        format!("Program data: {}", STANDARD.encode(&listed(1).data()[..12])),
+        "Program data: ".to_string(),
+        data_log(&listed(2)),
+        success(&marketplace::ID),
+    ];
+
+    assert_eq!(prices(&parse_logs(&logs)), [2]);
+}
+
+#[test]
+fn program_logs_do_not_open_or_close_frames() {
+    let other = Pubkey::new_unique();
+    let logs = [
+        invoke(&marketplace::ID, 1),
+        invoke(&other, 2),
+        // logged by the nested program, looks like a frame to a naive split
+        "Program log: success".to_string(),
+        "Program log: invoke [3]".to_string(),
+        format!("Program {other} consumed 1200 of 200000 compute units"),
+        data_log(&listed(1)),
+        success(&other),
+        "Program log: success".to_string(),
+        data_log(&listed(2)),
+        success(&marketplace::ID),
+    ];
+
+    assert_eq!(prices(&parse_logs(&logs)), [2]);
+}
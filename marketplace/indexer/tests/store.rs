@@ -0,0 +1,232 @@
+use anchor_lang::prelude::Pubkey;
+use marketplace::{
+    BidCancelled, FeeUpdated, Listed, Purchased, Rented, TradeAccepted, TradeProposed,
+};
+use marketplace_indexer::{EventFilter, MarketplaceEvent, Store};
+
+const SELLER: Pubkey = Pubkey::new_from_array([1; 32]);
+const COLLECTION: Pubkey = Pubkey::new_from_array([2; 32]);
+
+fn listed(mint: Pubkey, collection: Pubkey, timestamp: i64) -> MarketplaceEvent {
+    MarketplaceEvent::Listed(Listed {
+        seller: SELLER,
+        mint,
+        collection,
+        price: 1_000,
+        fee: 25,
+        expires_at: None,
+        allowed_buyer: None,
+        timestamp,
+    })
+}
+
+fn purchased(mint: Pubkey, timestamp: i64) -> MarketplaceEvent {
+    MarketplaceEvent::Purchased(Purchased {
+        buyer: Pubkey::new_unique(),
+        seller: SELLER,
+        mint,
+        collection: COLLECTION,
+        price: 1_000,
+        fee: 2,
+        royalties: None,
+        timestamp,
+    })
+}
+
+#[test]
+fn insert_and_query_round_trip() {
+    let mut store = Store::open_in_memory().unwrap();
+    let mint = Pubkey::new_unique();
+
+    let events = [listed(mint, COLLECTION, 10), purchased(mint, 20)];
+    assert_eq!(store.insert("sig", 5, &events).unwrap(), 2);
+
+    let rows = store.query(&EventFilter::default()).unwrap();
+    assert_eq!(rows.len(), 2);
+
+    let (listing, sale) = (&rows[0], &rows[1]);
+    assert_eq!(listing.kind, "listed");
+    assert_eq!(listing.signature, "sig");
+    assert_eq!(listing.slot, 5);
+    assert_eq!(listing.seller, Some(SELLER.to_string()));
+    assert_eq!(listing.mint, Some(mint.to_string()));
+    assert_eq!(listing.collection, Some(COLLECTION.to_string()));
+    assert_eq!(listing.price, Some(1_000));
+    assert_eq!(listing.fee_bps, Some(25));
+    assert_eq!(listing.fee_lamports, None);
+    assert_eq!(listing.buyer, None);
+
+    assert_eq!(sale.kind, "purchased");
+    assert_eq!(sale.fee_lamports, Some(2));
+    assert_eq!(sale.fee_bps, None);
+    assert_eq!(sale.royalties, None);
+    assert!(sale.buyer.is_some());
+    assert_eq!(sale.timestamp, 20);
+}
+
+#[test]
+fn ingesting_twice_is_a_no_op() {
+    let mut store = Store::open_in_memory().unwrap();
+    let events = [listed(Pubkey::new_unique(), COLLECTION, 10)];
+
+    assert_eq!(store.insert("sig", 5, &events).unwrap(), 1);
+    assert_eq!(store.insert("sig", 5, &events).unwrap(), 0);
+    assert_eq!(store.query(&EventFilter::default()).unwrap().len(), 1);
+}
+
+#[test]
+fn filters_by_kind_collection_seller_and_time() {
+    let mut store = Store::open_in_memory().unwrap();
+    let other_collection = Pubkey::new_unique();
+    let bidder = Pubkey::new_unique();
+
+    store
+        .insert(
+            "a",
+            1,
+            &[
+                listed(Pubkey::new_unique(), COLLECTION, 10),
+                listed(Pubkey::new_unique(), other_collection, 20),
+                purchased(Pubkey::new_unique(), 30),
+            ],
+        )
+        .unwrap();
+    store
+        .insert(
+            "b",
+            2,
+            &[
+                MarketplaceEvent::BidCancelled(BidCancelled {
+                    bidder,
+                    target: COLLECTION,
+                    collection: true,
+                    price: 500,
+                    timestamp: 40,
+                }),
+                MarketplaceEvent::FeeUpdated(FeeUpdated {
+                    fee: 25,
+                    new_fee: 30,
+                    effective_at: 1_000,
+                    timestamp: 50,
+                }),
+            ],
+        )
+        .unwrap();
+
+    let query = |filter: EventFilter| -> Vec<i64> {
+        store
+            .query(&filter)
+            .unwrap()
+            .iter()
+            .map(|row| row.timestamp)
+            .collect()
+    };
+
+    assert_eq!(
+        query(EventFilter {
+            kind: Some("listed".to_string()),
+            ..Default::default()
+        }),
+        [10, 20]
+    );
+    assert_eq!(
+        query(EventFilter {
+            collection: Some(COLLECTION.to_string()),
+            ..Default::default()
+        }),
+        [10, 30, 40]
+    );
+    assert_eq!(
+        query(EventFilter {
+            seller: Some(SELLER.to_string()),
+            from: Some(20),
+            to: Some(30),
+            ..Default::default()
+        }),
+        [20, 30]
+    );
+
+    let fee_update = &store
+        .query(&EventFilter {
+            kind: Some("fee_updated".to_string()),
+            ..Default::default()
+        })
+        .unwrap()[0];
+    assert_eq!(fee_update.fee_bps, Some(30));
+    assert_eq!(fee_update.effective_at, Some(1_000));
+
+    let bid = &store
+        .query(&EventFilter {
+            kind: Some("bid_cancelled".to_string()),
+            ..Default::default()
+        })
+        .unwrap()[0];
+    assert_eq!(bid.buyer, Some(bidder.to_string()));
+    assert_eq!(bid.mint, None);
+}
+
+#[test]
+fn indexes_rentals_and_trades() {
+    let mut store = Store::open_in_memory().unwrap();
+    let (mint, renter, taker) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    store
+        .insert(
+            "sig",
+            1,
+            &[
+                MarketplaceEvent::Rented(Rented {
+                    renter,
+                    owner: SELLER,
+                    mint,
+                    collection: COLLECTION,
+                    price: 700,
+                    fee: 3,
+                    rented_until: 100,
+                    timestamp: 10,
+                }),
+                MarketplaceEvent::TradeProposed(TradeProposed {
+                    maker: SELLER,
+                    taker,
+                    id: 7,
+                    offered: vec![mint],
+                    requested: vec![Pubkey::new_unique()],
+                    lamports: 50,
+                    timestamp: 20,
+                }),
+                MarketplaceEvent::TradeAccepted(TradeAccepted {
+                    maker: SELLER,
+                    taker,
+                    id: 7,
+                    timestamp: 30,
+                }),
+            ],
+        )
+        .unwrap();
+
+    let rows = store.query(&EventFilter::default()).unwrap();
+    let (rental, proposal, acceptance) = (&rows[0], &rows[1], &rows[2]);
+
+    assert_eq!(rental.kind, "rented");
+    assert_eq!(rental.seller, Some(SELLER.to_string()));
+    assert_eq!(rental.buyer, Some(renter.to_string()));
+    assert_eq!(rental.mint, Some(mint.to_string()));
+    assert_eq!(rental.price, Some(700));
+    assert_eq!(rental.fee_lamports, Some(3));
+    assert_eq!(rental.effective_at, Some(100));
+    assert_eq!(rental.trade_id, None);
+
+    assert_eq!(proposal.kind, "trade_proposed");
+    assert_eq!(proposal.buyer, Some(taker.to_string()));
+    assert_eq!(proposal.price, Some(50));
+    assert_eq!(proposal.trade_id, Some(7));
+    assert_eq!(proposal.mint, None);
+
+    assert_eq!(acceptance.kind, "trade_accepted");
+    assert_eq!(acceptance.seller, Some(SELLER.to_string()));
+    assert_eq!(acceptance.trade_id, Some(7));
+}
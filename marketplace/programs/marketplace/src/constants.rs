@@ -2,3 +2,7 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+// most nfts on each side of a trade
+#[constant]
+pub const MAX_TRADE_NFTS: usize = 5;
@@ -16,4 +16,145 @@ pub enum MarketplaceErrors {
 
     #[msg("during frozen protocol, Delisting is allowed after 1 week delay")]
     FrozenDelistDelay,
+
+    #[msg("Bid price must be greater than zero")]
+    ZeroBid,
+
+    #[msg("Nft does not match the bid target")]
+    BidTargetMismatch,
+
+    #[msg("Nft is not part of a verified collection")]
+    UnverifiedCollection,
+
+    #[msg("Auction must end after it starts and after now")]
+    InvalidAuctionWindow,
+
+    #[msg("Auction hasn't started yet")]
+    AuctionNotStarted,
+
+    #[msg("Auction has ended")]
+    AuctionEnded,
+
+    #[msg("Auction hasn't ended yet")]
+    AuctionNotEnded,
+
+    #[msg("Bid is below reserve price or minimum increment")]
+    BidTooLow,
+
+    #[msg("Previous highest bidder must be passed to be refunded")]
+    MissingPreviousBidder,
+
+    #[msg("Floor price must not exceed start price and decay must end after it starts")]
+    InvalidPriceDecay,
+
+    #[msg("Listing expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Listing has expired")]
+    ListingExpired,
+
+    #[msg("Listing hasn't expired yet")]
+    ListingNotExpired,
+
+    #[msg("Collection is not enabled on this marketplace")]
+    CollectionDisabled,
+
+    #[msg("Collection is in frozen state")]
+    CollectionFrozen,
+
+    #[msg("Nft does not belong to the given collection")]
+    CollectionMismatch,
+
+    #[msg("Nft is not a programmable nft")]
+    NotProgrammable,
+
+    #[msg("Remaining accounts don't match the batch size")]
+    InvalidBatch,
+
+    #[msg("Batch account doesn't match its expected address or owner")]
+    InvalidBatchAccount,
+
+    #[msg("Sweep would exceed the maximum total spend")]
+    MaxSpendExceeded,
+
+    #[msg("No listing could be purchased")]
+    NothingSwept,
+
+    #[msg("Queued action can't be executed before its timelock elapsed")]
+    TimelockNotElapsed,
+
+    #[msg("A fee update is already queued")]
+    FeeUpdatePending,
+
+    #[msg("Referral share can be at most half of the protocol fee")]
+    MaxReferralShare,
+
+    #[msg("Referrer wallet doesn't match the referrer account")]
+    ReferrerMismatch,
+
+    #[msg("Referrer wallet is missing")]
+    MissingReferrer,
+
+    #[msg("Listing is reserved for another buyer")]
+    BuyerNotAllowed,
+
+    #[msg("Account is not a valid core asset")]
+    InvalidAsset,
+
+    #[msg("Asset id doesn't match the merkle tree leaf")]
+    AssetIdMismatch,
+
+    #[msg("Rental must last at least one day at a non zero price")]
+    InvalidRental,
+
+    #[msg("Nft is currently rented out")]
+    AlreadyRented,
+
+    #[msg("Nft is not rented out")]
+    NotRented,
+
+    #[msg("Rental period hasn't ended yet")]
+    RentalNotOver,
+
+    #[msg("Trade must offer and request between 1 and 5 nfts")]
+    InvalidTrade,
+
+    #[msg("Remaining accounts don't match the traded nfts")]
+    InvalidTradeAccounts,
+
+    #[msg("Trade account doesn't match its expected address, mint or owner")]
+    InvalidTradeAccount,
+
+    #[msg("Trade is reserved for another taker")]
+    TakerNotAllowed,
+
+    #[msg("Protocol is not in frozen state")]
+    ProtocolNotFrozen,
+
+    #[msg("Seller can't bid on their own auction")]
+    SellerCannotBid,
+
+    #[msg("Listing price is above the buyer's maximum price")]
+    PriceAboveMax,
+
+    #[msg("Expiry is only supported for vault custody listings")]
+    ExpiryNotSupported,
+
+    #[msg("Account required by the queued action is missing or doesn't match it")]
+    InvalidActionAccount,
+
+    #[msg("Listing custody isn't supported by this instruction")]
+    CustodyNotSupported,
+
+    #[msg("Compressed nft metadata doesn't match the leaf's data hash")]
+    MetadataHashMismatch,
+
+    #[msg("Mint is not an nft, it needs 0 decimals and a supply of 1")]
+    NotAnNft,
+
+    #[msg("Taker hasn't escrowed the requested nfts")]
+    TradeNotDeposited,
+
+    #[msg("Taker already escrowed the requested nfts")]
+    TradeAlreadyDeposited,
 }
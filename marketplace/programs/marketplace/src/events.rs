@@ -0,0 +1,229 @@
+use anchor_lang::prelude::*;
+
+use crate::AdminAction;
+
+// `mint` is the nft mint, the core asset or the compressed nft asset id depending on the listing kind
+
+#[event]
+pub struct Listed {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey, // default pubkey when the asset isn't part of a collection
+    pub price: u64,         // fixed price, or start price of a dutch auction
+    pub fee: u16,           // fee in bips snapshotted into the listing
+    pub expires_at: Option<i64>,
+    pub allowed_buyer: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Delisted {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub expired: bool, // returned by the `expire_listing` crank rather than the seller
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64, // new fixed price, or new start price of a dutch auction
+    pub expires_at: Option<i64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Purchased {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64,             // total lamports paid by the buyer
+    pub fee: u64,               // lamports sent to treasury
+    pub royalties: Option<u64>, // lamports sent to creators, None as the marketplace doesn't pay royalties yet
+    pub timestamp: i64,
+}
+
+// `target` is the nft mint, or the collection mint for collection-wide offers
+
+#[event]
+pub struct BidPlaced {
+    pub bidder: Pubkey,
+    pub target: Pubkey,
+    pub collection: bool, // `target` is a collection
+    pub price: u64,       // lamports escrowed in the bid
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidCancelled {
+    pub bidder: Pubkey,
+    pub target: Pubkey,
+    pub collection: bool,
+    pub price: u64, // lamports refunded to the bidder
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey, // default pubkey when the nft isn't part of a verified collection
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub fee: u16, // fee in bips snapshotted into the auction
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionBidPlaced {
+    pub bidder: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub amount: u64,                     // new highest bid
+    pub previous_bidder: Option<Pubkey>, // outbid wallet, refunded its bid
+    pub timestamp: i64,
+}
+
+// sold auctions emit `Purchased` instead
+#[event]
+pub struct AuctionClosed {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub refunded_bidder: Option<Pubkey>, // highest bidder refunded by `refund_auction`
+    pub refund: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RentalListed {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub price_per_day: u64,
+    pub days: u16,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RentalDelisted {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Rented {
+    pub renter: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64, // total lamports paid by the renter
+    pub fee: u64,   // lamports sent to treasury
+    pub rented_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Reclaimed {
+    pub renter: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeProposed {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub id: u64,
+    pub offered: Vec<Pubkey>,
+    pub requested: Vec<Pubkey>,
+    pub lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeCancelled {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeDeposited {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeWithdrawn {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeAccepted {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeUpdated {
+    pub fee: u16,     // fee charged until the new fee is applied
+    pub new_fee: u16, // pending fee
+    pub effective_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeApplied {
+    pub old_fee: u16,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FreezeStateChanged {
+    pub frozen: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionProposed {
+    pub id: u64,
+    pub action: AdminAction,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionExecuted {
+    pub id: u64,
+    pub action: AdminAction,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionCancelled {
+    pub id: u64,
+    pub action: AdminAction,
+    pub timestamp: i64,
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Bid, CollectionConfig, Global, Purchased};
+
+#[derive(Accounts)]
+pub struct AcceptBid<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::authority = bidder,
+        associated_token::mint = mint,
+    )]
+    pub bidder_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"bid", bidder.key().as_ref(), bid.target.as_ref()],
+        bump = bid.bump,
+        close = bidder,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+            ],
+            seeds::program = metadata_program.key(),
+            bump
+        )]
+    metadata: Account<'info, MetadataAccount>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> AcceptBid<'info> {
+    pub fn accept(&mut self) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        // same collection rules as listing, the nft has to be tradeable here
+        self.collection_config.validate_member(&self.metadata)?;
+        self.collection_config.validate_trading()?;
+        self.validate_target()?;
+
+        // deliver nft to the bidder
+        self.transfer_nft()?;
+
+        // pay escrowed sol to seller and cut protocol fee, rent goes back to bidder on close
+        self.pay_sol()
+    }
+
+    pub fn validate_target(&self) -> Result<()> {
+        // collection membership is already verified against the collection config
+        let target = if self.bid.collection {
+            self.collection_config.collection_mint
+        } else {
+            self.mint.key()
+        };
+        require_keys_eq!(
+            target,
+            self.bid.target,
+            MarketplaceErrors::BidTargetMismatch
+        );
+
+        Ok(())
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.seller_ata.to_account_info(),
+                    to: self.bidder_ata.to_account_info(),
+                    authority: self.seller.to_account_info(),
+                },
+            ),
+            1,
+        )
+    }
+
+    pub fn pay_sol(&mut self) -> Result<()> {
+        let price = self.bid.price;
+        let bips = self.collection_config.effective_fee(&self.global) as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        // bid account is owned by this program, so lamports can be moved out directly
+        self.bid.sub_lamports(price)?;
+        self.seller.add_lamports(price_minus_fee)?;
+        self.treasury.add_lamports(fee)?;
+
+        emit!(Purchased {
+            buyer: self.bidder.key(),
+            seller: self.seller.key(),
+            mint: self.mint.key(),
+            collection: self.collection_config.collection_mint,
+            price,
+            fee,
+            royalties: None,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
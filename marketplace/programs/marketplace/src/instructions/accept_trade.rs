@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::{error::MarketplaceErrors, events::TradeAccepted, CollectionConfig, Global, Trade};
+
+#[derive(Accounts)]
+pub struct AcceptTrade<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = taker @ MarketplaceErrors::TakerNotAllowed,
+        seeds = [b"trade", maker.key().as_ref(), trade.id.to_le_bytes().as_ref()],
+        bump = trade.bump,
+        close = maker,
+    )]
+    pub trade: Account<'info, Trade>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptTrade<'info> {
+    /// remaining accounts start with the collection config of each of `trade.collections`, in order
+    /// followed by, per offered nft, in offered order: [vault, taker_ata]
+    /// followed by, per requested nft, in requested order: [vault, maker_ata]
+    pub const ACCOUNTS_PER_NFT: usize = 2;
+
+    pub fn accept(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        // both sides have to be escrowed before they're swapped
+        require!(
+            self.trade.taker_deposited,
+            MarketplaceErrors::TradeNotDeposited
+        );
+
+        let (offered, requested) = (self.trade.offered.clone(), self.trade.requested.clone());
+        let collections = self.trade.collections.clone();
+        require!(
+            remaining_accounts.len()
+                == collections.len() + (offered.len() + requested.len()) * Self::ACCOUNTS_PER_NFT,
+            MarketplaceErrors::InvalidTradeAccounts
+        );
+        let (collection_configs, nft_accounts) = remaining_accounts.split_at(collections.len());
+        let (offered_accounts, requested_accounts) =
+            nft_accounts.split_at(offered.len() * Self::ACCOUNTS_PER_NFT);
+
+        // a collection disabled or frozen since the deposits blocks the swap, like it blocks purchases
+        for (collection_config, collection) in collection_configs.iter().zip(collections) {
+            Trade::validate_collection_config(collection_config, collection)?;
+            Account::<CollectionConfig>::try_from(collection_config)?.validate_trading()?;
+        }
+
+        // both sides settle atomically, any failing nft reverts the whole trade
+        // vault rent goes back to whoever paid it on deposit
+        let (maker, taker) = (self.maker.to_account_info(), self.taker.to_account_info());
+        let token_program = self.token_program.to_account_info();
+        for (accounts, mint) in offered_accounts.chunks(Self::ACCOUNTS_PER_NFT).zip(offered) {
+            let [vault, taker_ata] = accounts else {
+                return err!(MarketplaceErrors::InvalidTradeAccounts);
+            };
+            Trade::release_nft(mint, vault, taker_ata, taker.key(), &maker, &token_program)?;
+        }
+        for (accounts, mint) in requested_accounts
+            .chunks(Self::ACCOUNTS_PER_NFT)
+            .zip(requested)
+        {
+            let [vault, maker_ata] = accounts else {
+                return err!(MarketplaceErrors::InvalidTradeAccounts);
+            };
+            Trade::release_nft(mint, vault, maker_ata, maker.key(), &taker, &token_program)?;
+        }
+
+        // escrowed sol on top of the rent goes to the taker, rent goes back to maker on close
+        let lamports = self.trade.lamports;
+        if lamports > 0 {
+            self.trade.sub_lamports(lamports)?;
+            self.taker.add_lamports(lamports)?;
+        }
+
+        emit!(TradeAccepted {
+            maker: self.trade.maker,
+            taker: self.trade.taker,
+            id: self.trade.id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
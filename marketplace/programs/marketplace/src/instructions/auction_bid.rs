@@ -0,0 +1,116 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Auction, AuctionBidPlaced, Global};
+
+#[derive(Accounts)]
+pub struct AuctionBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    // outbid bidder getting refunded, None on the first bid
+    #[account(
+        mut,
+        address = auction.highest_bidder,
+    )]
+    pub previous_bidder: Option<SystemAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction.seller.as_ref(), auction.mint.as_ref()],
+        bump = auction.bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> AuctionBid<'info> {
+    pub fn bid(&mut self, amount: u64) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        // no shill bidding the price up from the seller's own wallet
+        require_keys_neq!(
+            self.bidder.key(),
+            self.auction.seller,
+            MarketplaceErrors::SellerCannotBid
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= self.auction.start_time,
+            MarketplaceErrors::AuctionNotStarted
+        );
+        require!(now < self.auction.end_time, MarketplaceErrors::AuctionEnded);
+
+        // first bid must meet reserve, later ones must raise by at least min_increment
+        let min_bid = if self.auction.highest_bid == 0 {
+            self.auction.reserve_price.max(1)
+        } else {
+            self.auction
+                .highest_bid
+                .checked_add(self.auction.min_increment)
+                .unwrap()
+        };
+        require!(amount >= min_bid, MarketplaceErrors::BidTooLow);
+
+        let previous_bidder = (self.auction.highest_bid > 0).then_some(self.auction.highest_bidder);
+        // deposit before moving lamports directly, the cpi would otherwise see an unbalanced caller
+        self.deposit_sol(amount)?;
+        self.refund_previous_bidder()?;
+
+        self.auction.highest_bid = amount;
+        self.auction.highest_bidder = *self.bidder.key;
+
+        emit!(AuctionBidPlaced {
+            bidder: self.auction.highest_bidder,
+            seller: self.auction.seller,
+            mint: self.auction.mint,
+            collection: self.auction.collection,
+            amount,
+            previous_bidder,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn refund_previous_bidder(&mut self) -> Result<()> {
+        let refund = self.auction.highest_bid;
+        if refund == 0 {
+            return Ok(());
+        }
+
+        let previous_bidder = self
+            .previous_bidder
+            .as_ref()
+            .ok_or(MarketplaceErrors::MissingPreviousBidder)?;
+
+        // auction account is owned by this program, so lamports can be moved out directly
+        self.auction.sub_lamports(refund)?;
+        previous_bidder.add_lamports(refund)?;
+
+        Ok(())
+    }
+
+    pub fn deposit_sol(&mut self, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.bidder.to_account_info(),
+                    to: self.auction.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+}
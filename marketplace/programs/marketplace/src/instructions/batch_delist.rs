@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{close_account, transfer, CloseAccount, Token, TokenAccount, Transfer};
+
+use crate::{error::MarketplaceErrors, Custody, Global, Offer};
+
+#[derive(Accounts)]
+pub struct BatchDelist<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> BatchDelist<'info> {
+    /// remaining accounts per nft: [mint, seller_ata, vault, listing]
+    pub const ACCOUNTS_PER_NFT: usize = 4;
+
+    pub fn batch_delist(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+
+        require!(
+            !remaining_accounts.is_empty()
+                && remaining_accounts
+                    .chunks_exact(Self::ACCOUNTS_PER_NFT)
+                    .remainder()
+                    .is_empty(),
+            MarketplaceErrors::InvalidBatch
+        );
+
+        // any failing nft reverts the whole batch
+        for accounts in remaining_accounts.chunks(Self::ACCOUNTS_PER_NFT) {
+            self.delist_one(accounts)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn delist_one(&mut self, accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let [mint, seller_ata, vault, listing] = accounts else {
+            return err!(MarketplaceErrors::InvalidBatch);
+        };
+
+        let seller_key = self.seller.key();
+        let (listing_key, _) = Pubkey::find_program_address(
+            &[b"listing", seller_key.as_ref(), mint.key.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            listing.key(),
+            listing_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        let offer = Account::<Offer>::try_from(listing)?;
+        require!(
+            offer.custody == Custody::Vault,
+            MarketplaceErrors::CustodyNotSupported
+        );
+
+        let seller_token = Account::<TokenAccount>::try_from(seller_ata)?;
+        require_keys_eq!(
+            seller_token.owner,
+            seller_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", mint.key.as_ref()], &crate::ID);
+        require_keys_eq!(
+            vault.key(),
+            vault_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint.key.as_ref(), &[vault_bump]]];
+
+        // withdraw nft back to seller and close the vault
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: vault.clone(),
+                    to: seller_ata.clone(),
+                    authority: vault.clone(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.clone(),
+                destination: self.seller.to_account_info(),
+                authority: vault.clone(),
+            },
+            signer_seeds,
+        ))?;
+
+        offer.emit_delisted(mint.key(), false)?;
+        offer.close(self.seller.to_account_info())
+    }
+}
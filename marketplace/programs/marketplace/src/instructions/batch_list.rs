@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{Metadata, MetadataAccount},
+    token::{
+        initialize_account3, transfer, InitializeAccount3, Mint, Token, TokenAccount, Transfer,
+    },
+};
+
+use crate::{
+    error::MarketplaceErrors, utils::create_pda_account, CollectionConfig, Custody, Global, Offer,
+};
+
+#[derive(Accounts)]
+pub struct BatchList<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", collection_mint.key().as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> BatchList<'info> {
+    /// remaining accounts per nft: [mint, seller_ata, vault, listing, metadata]
+    pub const ACCOUNTS_PER_NFT: usize = 5;
+
+    pub fn batch_list(
+        &mut self,
+        prices: &[u64],
+        expires_at: Option<i64>,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        self.collection_config.validate_trading()?;
+        Offer::validate_expiry(expires_at, Custody::Vault, Clock::get()?.unix_timestamp)?;
+
+        require!(
+            !prices.is_empty() && remaining_accounts.len() == prices.len() * Self::ACCOUNTS_PER_NFT,
+            MarketplaceErrors::InvalidBatch
+        );
+
+        // any failing nft reverts the whole batch
+        for (accounts, price) in remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_NFT)
+            .zip(prices)
+        {
+            self.list_one(accounts, *price, expires_at)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn list_one(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        price: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let [mint, seller_ata, vault, listing, metadata] = accounts else {
+            return err!(MarketplaceErrors::InvalidBatch);
+        };
+
+        let seller_token = Account::<TokenAccount>::try_from(seller_ata)?;
+        require_keys_eq!(
+            seller_token.owner,
+            self.seller.key(),
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        require_keys_eq!(
+            seller_token.mint,
+            mint.key(),
+            MarketplaceErrors::InvalidBatchAccount
+        );
+
+        // same collection checks as single listings
+        let metadata_program = self.metadata_program.key();
+        let (metadata_key, _) = Pubkey::find_program_address(
+            &[b"metadata", metadata_program.as_ref(), mint.key.as_ref()],
+            &metadata_program,
+        );
+        require_keys_eq!(
+            metadata.key(),
+            metadata_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        let metadata = Account::<MetadataAccount>::try_from(metadata)?;
+        self.collection_config.validate_member(&metadata)?;
+
+        self.init_vault(mint, vault)?;
+        self.init_listing(mint, listing, price, expires_at)?;
+
+        // deposit nft
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: seller_ata.to_account_info(),
+                    to: vault.to_account_info(),
+                    authority: self.seller.to_account_info(),
+                },
+            ),
+            1,
+        )
+    }
+
+    // same as `init_if_needed` on the vault of a single listing
+    pub fn init_vault(&self, mint: &AccountInfo<'info>, vault: &AccountInfo<'info>) -> Result<()> {
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", mint.key.as_ref()], &crate::ID);
+        require_keys_eq!(
+            vault.key(),
+            vault_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+
+        if !vault.data_is_empty() {
+            return Ok(());
+        }
+
+        create_pda_account(
+            &self.seller.to_account_info(),
+            vault,
+            TokenAccount::LEN,
+            &self.token_program.key(),
+            &[&[b"vault", mint.key.as_ref(), &[vault_bump]]],
+            &self.system_program.to_account_info(),
+        )?;
+
+        initialize_account3(CpiContext::new(
+            self.token_program.to_account_info(),
+            InitializeAccount3 {
+                account: vault.clone(),
+                mint: mint.clone(),
+                authority: vault.clone(),
+            },
+        ))
+    }
+
+    // same as `init` on the listing of a single listing, fails if already listed
+    pub fn init_listing(
+        &self,
+        mint: &AccountInfo<'info>,
+        listing: &AccountInfo<'info>,
+        price: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let seller_key = self.seller.key();
+        let (listing_key, listing_bump) = Pubkey::find_program_address(
+            &[b"listing", seller_key.as_ref(), mint.key.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            listing.key(),
+            listing_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+
+        create_pda_account(
+            &self.seller.to_account_info(),
+            listing,
+            8 + Offer::INIT_SPACE,
+            &crate::ID,
+            &[&[
+                b"listing",
+                seller_key.as_ref(),
+                mint.key.as_ref(),
+                &[listing_bump],
+            ]],
+            &self.system_program.to_account_info(),
+        )?;
+
+        let offer = Offer {
+            seller: seller_key,
+            price,
+            decay: None,
+            expires_at,
+            collection: self.collection_mint.key(),
+            allowed_buyer: None,
+            fee: self.collection_config.effective_fee(&self.global),
+            referrer: None,
+            referrer_share: 0,
+            custody: Custody::Vault,
+            bump: listing_bump,
+        };
+        offer.try_serialize(&mut &mut listing.try_borrow_mut_data()?[..])?;
+
+        offer.emit_listed(mint.key())
+    }
+}
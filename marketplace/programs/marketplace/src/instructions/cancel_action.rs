@@ -0,0 +1,44 @@
+use crate::{ActionCancelled, AdminAction, Global, QueuedAction};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelAction<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global.bump,
+        has_one = admin,
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        mut,
+        seeds = [b"action", queued_action.id.to_le_bytes().as_ref()],
+        bump = queued_action.bump,
+        has_one = proposer,
+        close = proposer,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+}
+
+impl<'info> CancelAction<'info> {
+    pub fn cancel(&mut self) -> Result<()> {
+        // cancelled fee update frees the slot for a new one
+        if let AdminAction::UpdateFee { .. } = self.queued_action.action {
+            self.global.fee_action = None;
+        }
+
+        emit!(ActionCancelled {
+            id: self.queued_action.id,
+            action: self.queued_action.action,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
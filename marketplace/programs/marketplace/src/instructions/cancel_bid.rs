@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::{Bid, BidCancelled, Global};
+
+#[derive(Accounts)]
+pub struct CancelBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    // escrowed lamports go back to bidder along with the rent
+    #[account(
+        mut,
+        seeds = [b"bid", bidder.key().as_ref(), bid.target.as_ref()],
+        bump = bid.bump,
+        close = bidder,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+}
+
+impl<'info> CancelBid<'info> {
+    pub fn cancel(&mut self) -> Result<()> {
+        // same as delisting, bidders can only pull out of frozen protocol after 1 week delay
+        self.global.check_frozen_withdrawal()?;
+
+        emit!(BidCancelled {
+            bidder: self.bid.bidder,
+            target: self.bid.target,
+            collection: self.bid.collection,
+            price: self.bid.price,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
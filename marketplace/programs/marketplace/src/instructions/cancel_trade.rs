@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::{error::MarketplaceErrors, events::TradeCancelled, Global, Trade};
+
+#[derive(Accounts)]
+pub struct CancelTrade<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    // gets back the requested nfts and their vault rent if it already deposited them
+    #[account(mut, address = trade.taker)]
+    pub taker: SystemAccount<'info>,
+
+    // escrowed lamports go back to the maker along with the rent
+    #[account(
+        mut,
+        has_one = maker,
+        seeds = [b"trade", maker.key().as_ref(), trade.id.to_le_bytes().as_ref()],
+        bump = trade.bump,
+        close = maker,
+    )]
+    pub trade: Account<'info, Trade>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> CancelTrade<'info> {
+    /// remaining accounts per offered nft, in offered order: [vault, maker_ata]
+    /// followed by, once the taker deposited, per requested nft, in requested order: [vault, taker_ata]
+    pub const ACCOUNTS_PER_NFT: usize = 2;
+
+    pub fn cancel(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        // Only cancelling is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+
+        let offered = self.trade.offered.clone();
+        let requested = if self.trade.taker_deposited {
+            self.trade.requested.clone()
+        } else {
+            vec![]
+        };
+        require!(
+            remaining_accounts.len() == (offered.len() + requested.len()) * Self::ACCOUNTS_PER_NFT,
+            MarketplaceErrors::InvalidTradeAccounts
+        );
+        let (offered_accounts, requested_accounts) =
+            remaining_accounts.split_at(offered.len() * Self::ACCOUNTS_PER_NFT);
+
+        // each side gets its own nfts and vault rent back
+        let (maker, taker) = (self.maker.to_account_info(), self.taker.to_account_info());
+        let token_program = self.token_program.to_account_info();
+        for (accounts, mint) in offered_accounts.chunks(Self::ACCOUNTS_PER_NFT).zip(offered) {
+            let [vault, maker_ata] = accounts else {
+                return err!(MarketplaceErrors::InvalidTradeAccounts);
+            };
+            Trade::release_nft(mint, vault, maker_ata, maker.key(), &maker, &token_program)?;
+        }
+        for (accounts, mint) in requested_accounts
+            .chunks(Self::ACCOUNTS_PER_NFT)
+            .zip(requested)
+        {
+            let [vault, taker_ata] = accounts else {
+                return err!(MarketplaceErrors::InvalidTradeAccounts);
+            };
+            Trade::release_nft(mint, vault, taker_ata, taker.key(), &taker, &token_program)?;
+        }
+
+        emit!(TradeCancelled {
+            maker: self.trade.maker,
+            taker: self.trade.taker,
+            id: self.trade.id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
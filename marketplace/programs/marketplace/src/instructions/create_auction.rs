@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Auction, AuctionCreated, CollectionConfig, Global};
+
+#[derive(Accounts)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Auction::INIT_SPACE,
+        seeds = [b"auction", seller.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> CreateAuction<'info> {
+    pub fn create(
+        &mut self,
+        reserve_price: u64,
+        min_increment: u64,
+        start_time: i64,
+        end_time: i64,
+        bumps: &CreateAuctionBumps,
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        // same collection rules as listing, the nft has to be tradeable here
+        self.collection_config.validate_member(&self.metadata)?;
+        self.collection_config.validate_trading()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            start_time < end_time && end_time > now,
+            MarketplaceErrors::InvalidAuctionWindow
+        );
+
+        // same custody as fixed price listings
+        self.deposit_nft()?;
+
+        self.auction.set_inner(Auction {
+            seller: (*self.seller.key),
+            mint: (self.mint.key()),
+            collection: (self.collection_config.collection_mint),
+            reserve_price: (reserve_price),
+            min_increment: (min_increment),
+            start_time: (start_time),
+            end_time: (end_time),
+            highest_bid: (0),
+            highest_bidder: (Pubkey::default()),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            bump: (bumps.auction),
+        });
+
+        emit!(AuctionCreated {
+            seller: self.auction.seller,
+            mint: self.auction.mint,
+            collection: self.auction.collection,
+            reserve_price,
+            min_increment,
+            start_time,
+            end_time,
+            fee: self.auction.fee,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft(&mut self) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.seller_ata.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.seller.to_account_info(),
+                },
+            ),
+            1,
+        )
+    }
+}
@@ -1,11 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    metadata::{Metadata, MetadataAccount},
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    metadata::Metadata,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
 };
 
-use crate::{error::MarketplaceErrors, Global, Offer};
+use crate::{error::MarketplaceErrors, Custody, Global, Offer};
 
 #[derive(Accounts)]
 pub struct Delist<'info> {
@@ -28,7 +28,6 @@ pub struct Delist<'info> {
         bump,
         token::authority = vault,
         token::mint = mint,
-        close = seller,
     )]
     pub vault: Account<'info, TokenAccount>,
 
@@ -56,19 +55,22 @@ pub struct Delist<'info> {
 impl<'info> Delist<'info> {
     pub fn delist(&mut self, bumps: &DelistBumps) -> Result<()> {
         // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
-        if self.global.frozen {
-            let now = Clock::get()?.unix_timestamp;
-            const ONE_WEEK: i64 = 7 * 24 * 60 * 60; // 604,800 seconds
-            require!(
-                now - self.global.frozen_at >= ONE_WEEK,
-                MarketplaceErrors::FrozenDelistDelay
-            );
-        }
+        self.global.check_frozen_withdrawal()?;
+        // other custodies are unwound by their own delist instruction
+        require!(
+            self.listing.custody == Custody::Vault,
+            MarketplaceErrors::CustodyNotSupported
+        );
+        self.listing.emit_delisted(self.mint.key(), false)?;
+
         // close listing and withdraw nft back to seller
         self.withdraw_nft(bumps)
     }
 
     pub fn withdraw_nft(&mut self, bumps: &DelistBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
         transfer(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
@@ -77,9 +79,19 @@ impl<'info> Delist<'info> {
                     to: self.seller_ata.to_account_info(),
                     authority: self.vault.to_account_info(),
                 },
-                &[&[b"vault", self.mint.key().as_ref(), &[bumps.vault]]],
+                signer_seeds,
             ),
             1,
-        )
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
     }
 }
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::{TransferCpi, TransferCpiAccounts};
+
+use crate::{error::MarketplaceErrors, CompressedLeaf, Global, Offer};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct DelistCompressed<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: bubblegum tree config, validated by bubblegum program
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: merkle tree holding the leaf, validated by account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), asset_id.as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    /// CHECK: bubblegum program
+    #[account(address = mpl_bubblegum::ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program, validated by bubblegum program
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: account compression program, validated by bubblegum program
+    pub compression_program: UncheckedAccount<'info>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> DelistCompressed<'info> {
+    pub fn delist(
+        &mut self,
+        asset_id: Pubkey,
+        leaf: CompressedLeaf,
+        proof: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+
+        require_keys_eq!(
+            leaf.asset_id(&self.merkle_tree.key()),
+            asset_id,
+            MarketplaceErrors::AssetIdMismatch
+        );
+        self.listing.emit_delisted(asset_id, false)?;
+
+        // close listing and hand the leaf back to seller
+        self.withdraw_leaf(asset_id, &leaf, proof)
+    }
+
+    pub fn withdraw_leaf(
+        &mut self,
+        asset_id: Pubkey,
+        leaf: &CompressedLeaf,
+        proof: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let listing = self.listing.to_account_info();
+        let proof: Vec<_> = proof.iter().map(|node| (node, false, false)).collect();
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            asset_id.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        TransferCpi::new(
+            &self.bubblegum_program.to_account_info(),
+            TransferCpiAccounts {
+                tree_config: &self.tree_config.to_account_info(),
+                leaf_owner: (&listing, true),
+                leaf_delegate: (&listing, false),
+                new_leaf_owner: &self.seller.to_account_info(),
+                merkle_tree: &self.merkle_tree.to_account_info(),
+                log_wrapper: &self.log_wrapper.to_account_info(),
+                compression_program: &self.compression_program.to_account_info(),
+                system_program: &self.system_program.to_account_info(),
+            },
+            leaf.transfer_args(),
+        )
+        .invoke_signed_with_remaining_accounts(signer_seeds, &proof)?;
+
+        Ok(())
+    }
+}
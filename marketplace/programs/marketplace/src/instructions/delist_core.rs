@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use mpl_core::instructions::{TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs};
+
+use crate::{Global, Offer};
+
+#[derive(Accounts)]
+pub struct DelistCore<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: core asset, validated by core program
+    #[account(mut, owner = mpl_core::ID)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: core collection of the asset, validated by core program
+    #[account(mut)]
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), asset.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    /// CHECK: core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> DelistCore<'info> {
+    pub fn delist(&mut self) -> Result<()> {
+        // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        self.listing.emit_delisted(self.asset.key(), false)?;
+
+        // close listing and withdraw asset back to seller
+        self.withdraw_asset()
+    }
+
+    pub fn withdraw_asset(&mut self) -> Result<()> {
+        let listing = self.listing.to_account_info();
+        let collection = self.collection.as_ref().map(|c| c.to_account_info());
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let asset_key = self.asset.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            asset_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        TransferV1Cpi::new(
+            &self.mpl_core_program.to_account_info(),
+            TransferV1CpiAccounts {
+                asset: &self.asset.to_account_info(),
+                collection: collection.as_ref(),
+                payer: &self.seller.to_account_info(),
+                authority: Some(&listing),
+                new_owner: &self.seller.to_account_info(),
+                system_program: Some(&self.system_program.to_account_info()),
+                log_wrapper: None,
+            },
+            TransferV1InstructionArgs {
+                compression_proof: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::instructions::{
+            ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts,
+        },
+        MasterEditionAccount, Metadata,
+    },
+    token::{revoke, Mint, Revoke, Token, TokenAccount},
+};
+
+use crate::{Global, Offer};
+
+#[derive(Accounts)]
+pub struct DelistDelegated<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> DelistDelegated<'info> {
+    pub fn delist(&mut self) -> Result<()> {
+        // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        self.listing.emit_delisted(self.mint.key(), false)?;
+
+        // thaw nft and take delegation back from the listing
+        self.unfreeze_nft()
+    }
+
+    pub fn unfreeze_nft(&mut self) -> Result<()> {
+        let token_program = self.token_program.to_account_info();
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        ThawDelegatedAccountCpi::new(
+            &self.metadata_program.to_account_info(),
+            ThawDelegatedAccountCpiAccounts {
+                delegate: &self.listing.to_account_info(),
+                token_account: &self.seller_ata.to_account_info(),
+                edition: &self.edition.to_account_info(),
+                mint: &self.mint.to_account_info(),
+                token_program: &token_program,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        revoke(CpiContext::new(
+            token_program,
+            Revoke {
+                source: self.seller_ata.to_account_info(),
+                authority: self.seller.to_account_info(),
+            },
+        ))
+    }
+}
@@ -0,0 +1,190 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        mpl_token_metadata::instructions::{
+            TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs,
+        },
+        MasterEditionAccount, Metadata, MetadataAccount,
+    },
+    token::{close_account, CloseAccount, Mint, Token, TokenAccount},
+};
+
+use crate::{Global, Offer};
+
+#[derive(Accounts)]
+pub struct DelistPnft<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by seeds, created by token metadata program on transfer
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            seller_ata.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub seller_token_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::authority = listing,
+        associated_token::mint = mint,
+    )]
+    pub listing_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by seeds and token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            listing_ata.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub listing_token_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    /// CHECK: rule set program, validated by token metadata program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: rule set of the pnft, validated by token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> DelistPnft<'info> {
+    pub fn delist(&mut self) -> Result<()> {
+        // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        self.listing.emit_delisted(self.mint.key(), false)?;
+
+        // close listing and withdraw pnft back to seller
+        self.withdraw_pnft()
+    }
+
+    pub fn withdraw_pnft(&mut self) -> Result<()> {
+        let seller = self.seller.to_account_info();
+        let listing = self.listing.to_account_info();
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        TransferV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            TransferV1CpiAccounts {
+                token: &self.listing_ata.to_account_info(),
+                token_owner: &listing,
+                destination_token: &self.seller_ata.to_account_info(),
+                destination_owner: &seller,
+                mint: &self.mint.to_account_info(),
+                metadata: &self.metadata.to_account_info(),
+                edition: Some(&self.edition.to_account_info()),
+                token_record: Some(&self.listing_token_record.to_account_info()),
+                destination_token_record: Some(&self.seller_token_record.to_account_info()),
+                authority: &listing,
+                payer: &seller,
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instructions.to_account_info(),
+                spl_token_program: &self.token_program.to_account_info(),
+                spl_ata_program: &self.associated_token_program.to_account_info(),
+                authorization_rules_program: self
+                    .authorization_rules_program
+                    .as_ref()
+                    .map(|program| program.as_ref()),
+                authorization_rules: self
+                    .authorization_rules
+                    .as_ref()
+                    .map(|rules| rules.as_ref()),
+            },
+            TransferV1InstructionArgs {
+                amount: 1,
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        // emptied listing ata rent goes back to the seller who paid for it
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.listing_ata.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: listing,
+            },
+            signer_seeds,
+        ))
+    }
+}
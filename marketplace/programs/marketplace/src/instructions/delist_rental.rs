@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Global, Rental};
+
+#[derive(Accounts)]
+pub struct DelistRental<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::authority = owner,
+        associated_token::mint = mint,
+    )]
+    pub owner_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"rental", owner.key().as_ref(), mint.key().as_ref()],
+        bump = rental.bump,
+        close = owner,
+    )]
+    pub rental: Account<'info, Rental>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> DelistRental<'info> {
+    pub fn delist(&mut self, bumps: &DelistRentalBumps) -> Result<()> {
+        // rented nfts come back through `reclaim` once the rental is over
+        require!(
+            self.rental.renter.is_none(),
+            MarketplaceErrors::AlreadyRented
+        );
+
+        // Only delisting is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        self.rental.emit_delisted()?;
+
+        // close rental and withdraw nft back to owner
+        self.withdraw_nft(bumps)
+    }
+
+    pub fn withdraw_nft(&mut self, bumps: &DelistRentalBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.owner_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::{error::MarketplaceErrors, events::TradeDeposited, Global, Trade};
+
+#[derive(Accounts)]
+pub struct DepositTrade<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = taker @ MarketplaceErrors::TakerNotAllowed,
+        seeds = [b"trade", maker.key().as_ref(), trade.id.to_le_bytes().as_ref()],
+        bump = trade.bump,
+    )]
+    pub trade: Account<'info, Trade>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> DepositTrade<'info> {
+    /// remaining accounts per requested nft, in requested order:
+    /// [mint, taker_ata, vault, metadata, collection_config]
+    pub const ACCOUNTS_PER_NFT: usize = 5;
+
+    pub fn deposit(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require!(
+            !self.trade.taker_deposited,
+            MarketplaceErrors::TradeAlreadyDeposited
+        );
+
+        let requested = self.trade.requested.clone();
+        require!(
+            remaining_accounts.len() == requested.len() * Self::ACCOUNTS_PER_NFT,
+            MarketplaceErrors::InvalidTradeAccounts
+        );
+
+        // any failing nft reverts the whole deposit
+        for (accounts, mint) in remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_NFT)
+            .zip(requested)
+        {
+            self.deposit_nft(accounts, mint)?;
+        }
+
+        self.trade.taker_deposited = true;
+
+        emit!(TradeDeposited {
+            maker: self.trade.maker,
+            taker: self.trade.taker,
+            id: self.trade.id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        mint: Pubkey,
+    ) -> Result<()> {
+        let [mint_account, taker_ata, vault, metadata, collection_config] = accounts else {
+            return err!(MarketplaceErrors::InvalidTradeAccounts);
+        };
+        require_keys_eq!(
+            mint_account.key(),
+            mint,
+            MarketplaceErrors::InvalidTradeAccount
+        );
+
+        // same collection rules as the maker's side
+        let collection = Trade::validate_collection(mint, metadata, collection_config)?;
+        self.trade.add_collection(collection);
+
+        Trade::deposit_nft(
+            &self.taker.to_account_info(),
+            mint_account,
+            taker_ata,
+            vault,
+            &self.token_program.to_account_info(),
+            &self.system_program.to_account_info(),
+        )
+    }
+}
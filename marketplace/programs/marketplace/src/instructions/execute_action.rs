@@ -0,0 +1,107 @@
+use crate::{
+    error::MarketplaceErrors, ActionExecuted, AdminAction, CollectionConfig, FeeApplied, Global,
+    QueuedAction, Referrer,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global.bump,
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        mut,
+        seeds = [b"action", queued_action.id.to_le_bytes().as_ref()],
+        bump = queued_action.bump,
+        has_one = proposer,
+        close = proposer,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    // only needed by `SetCollectionFee`
+    #[account(
+        mut,
+        seeds = [b"collection", collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Option<Account<'info, CollectionConfig>>,
+
+    // only needed by `SetReferrerShare`
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.wallet.as_ref()],
+        bump = referrer.bump,
+    )]
+    pub referrer: Option<Account<'info, Referrer>>,
+}
+
+impl<'info> ExecuteAction<'info> {
+    pub fn execute(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            now >= self.queued_action.executable_at,
+            MarketplaceErrors::TimelockNotElapsed
+        );
+
+        match self.queued_action.action {
+            AdminAction::UpdateFee { fee } => {
+                emit!(FeeApplied {
+                    old_fee: self.global.fee,
+                    fee,
+                    timestamp: now,
+                });
+
+                self.global.fee = fee;
+                self.global.fee_effective_at = now;
+                self.global.fee_action = None;
+            }
+            AdminAction::SetTreasury { treasury } => self.global.treasury = treasury,
+            AdminAction::TransferAdmin { admin } => self.global.admin = admin,
+            AdminAction::SetCollectionFee {
+                collection_mint,
+                fee,
+            } => {
+                let collection_config = self
+                    .collection_config
+                    .as_mut()
+                    .ok_or(MarketplaceErrors::InvalidActionAccount)?;
+                require_keys_eq!(
+                    collection_config.collection_mint,
+                    collection_mint,
+                    MarketplaceErrors::InvalidActionAccount
+                );
+
+                collection_config.fee = fee;
+            }
+            AdminAction::SetReferrerShare { wallet, share } => {
+                let referrer = self
+                    .referrer
+                    .as_mut()
+                    .ok_or(MarketplaceErrors::InvalidActionAccount)?;
+                require_keys_eq!(
+                    referrer.wallet,
+                    wallet,
+                    MarketplaceErrors::InvalidActionAccount
+                );
+
+                referrer.share = share;
+            }
+        }
+
+        emit!(ActionExecuted {
+            id: self.queued_action.id,
+            action: self.queued_action.action,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+}
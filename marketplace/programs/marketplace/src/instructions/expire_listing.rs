@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Custody, Global, Offer};
+
+#[derive(Accounts)]
+pub struct ExpireListing<'info> {
+    // anyone can crank expired listings
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = listing.seller)]
+    pub seller: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ExpireListing<'info> {
+    pub fn expire(&mut self, bumps: &ExpireListingBumps) -> Result<()> {
+        require!(
+            self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceErrors::ListingNotExpired
+        );
+        require!(
+            self.listing.custody == Custody::Vault,
+            MarketplaceErrors::ExpiryNotSupported
+        );
+
+        // same as delisting, nothing leaves a frozen protocol before 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        self.listing.emit_delisted(self.mint.key(), true)?;
+
+        self.withdraw_nft(bumps)
+    }
+
+    pub fn withdraw_nft(&mut self, bumps: &ExpireListingBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.seller_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
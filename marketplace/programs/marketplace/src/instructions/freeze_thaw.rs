@@ -1,4 +1,4 @@
-use crate::{error::MarketplaceErrors, Global};
+use crate::{error::MarketplaceErrors, FreezeStateChanged, Global};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -19,14 +19,21 @@ impl<'info> FreezeThaw<'info> {
     pub fn set(&mut self, freeze: bool) -> Result<()> {
         require!(self.global.frozen != freeze, MarketplaceErrors::SameState);
 
+        let now = Clock::get()?.unix_timestamp;
+
         if freeze {
-            self.global.frozen_at = Clock::get()?.unix_timestamp;
+            self.global.frozen_at = now;
         } else {
             self.global.frozen_at = 0;
         }
 
         self.global.frozen = freeze;
 
+        emit!(FreezeStateChanged {
+            frozen: freeze,
+            timestamp: now,
+        });
+
         Ok(())
     }
 }
@@ -0,0 +1,30 @@
+use crate::{error::MarketplaceErrors, FeeSchedule, Global, QueuedAction};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct GetFees<'info> {
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump,
+    )]
+    pub global: Account<'info, Global>,
+
+    // queued `UpdateFee` action, only needed while a fee change is pending
+    #[account(
+        seeds = [b"action", fee_action.id.to_le_bytes().as_ref()],
+        bump = fee_action.bump,
+    )]
+    pub fee_action: Option<Account<'info, QueuedAction>>,
+}
+
+impl<'info> GetFees<'info> {
+    pub fn get(&self) -> Result<FeeSchedule> {
+        let fee_action = self.fee_action.as_deref();
+        require!(
+            fee_action.map(|queued| queued.id) == self.global.fee_action,
+            MarketplaceErrors::InvalidActionAccount
+        );
+
+        Ok(self.global.fee_schedule(fee_action))
+    }
+}
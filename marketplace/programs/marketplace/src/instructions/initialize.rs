@@ -33,10 +33,11 @@ impl<'info> Initialize<'info> {
             admin: (*self.admin.key),
             treasury: (treasury),
             fee: (fee),
-            new_fee: (0),
-            new_fee_at: (0),
+            fee_effective_at: (Clock::get()?.unix_timestamp),
+            fee_action: (None),
             frozen: (false),
             frozen_at: (0),
+            action_count: (0),
             bump: (bumps.global),
         });
         Ok(())
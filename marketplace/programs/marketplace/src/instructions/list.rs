@@ -5,7 +5,9 @@ use anchor_spl::{
     token::{transfer, Mint, Token, TokenAccount, Transfer},
 };
 
-use crate::{error::MarketplaceErrors, Global, Offer};
+use crate::{
+    error::MarketplaceErrors, CollectionConfig, Custody, DutchAuction, Global, Offer, Referrer,
+};
 
 #[derive(Accounts)]
 pub struct List<'info> {
@@ -49,6 +51,15 @@ pub struct List<'info> {
     )]
     pub global: Account<'info, Global>,
 
+    #[account(
+        seeds = [b"collection", collection_mint.key().as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    // partner frontend the nft is listed through, its share is snapshotted into the listing
+    pub referrer: Option<Account<'info, Referrer>>,
+
     #[account(
         seeds = [
             b"metadata",
@@ -68,9 +79,24 @@ pub struct List<'info> {
 }
 
 impl<'info> List<'info> {
-    pub fn list(&mut self, price: u64, bumps: &ListBumps) -> Result<()> {
+    pub fn list(
+        &mut self,
+        price: u64,
+        decay: Option<DutchAuction>,
+        expires_at: Option<i64>,
+        allowed_buyer: Option<Pubkey>,
+        bumps: &ListBumps,
+    ) -> Result<()> {
         require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
 
+        self.validate_collection()?;
+
+        Offer::validate_expiry(expires_at, Custody::Vault, Clock::get()?.unix_timestamp)?;
+
+        if let Some(decay) = decay {
+            decay.validate(price)?;
+        }
+
         // transfer nft to the offer account
         self.deposit_nft()?;
 
@@ -78,15 +104,30 @@ impl<'info> List<'info> {
         self.listing.set_inner(Offer {
             seller: (*self.seller.key),
             price: (price),
+            decay: (decay),
+            expires_at: (expires_at),
+            collection: (self.collection_mint.key()),
+            allowed_buyer: (allowed_buyer),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            referrer: (self.referrer.as_ref().map(|referrer| referrer.wallet)),
+            referrer_share: (self.referrer.as_ref().map_or(0, |referrer| referrer.share)),
+            custody: (Custody::Vault),
             bump: (bumps.listing),
         });
 
+        self.listing.emit_listed(self.mint.key())?;
+
         Ok(())
     }
 
-    pub fn deposit_nft(&mut self) -> Result<()> {
-        //@later :: validate nft collection
+    pub fn validate_collection(&self) -> Result<()> {
+        self.collection_config.validate_member(&self.metadata)?;
 
+        // only curated collections trade on this marketplace
+        self.collection_config.validate_trading()
+    }
+
+    pub fn deposit_nft(&mut self) -> Result<()> {
         // deposit nft
         let token_program = self.token_program.to_account_info();
         transfer(
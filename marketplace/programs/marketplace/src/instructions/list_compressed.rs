@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::{TransferCpi, TransferCpiAccounts};
+
+use crate::{
+    error::MarketplaceErrors, CollectionConfig, CompressedLeaf, Custody, DutchAuction, Global,
+    Offer, Referrer,
+};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct ListCompressed<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: bubblegum tree config, validated by bubblegum program
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: merkle tree holding the leaf, validated by account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"listing", seller.key().as_ref(), asset_id.as_ref()],
+        bump,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    // partner frontend the nft is listed through, its share is snapshotted into the listing
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    /// CHECK: bubblegum program
+    #[account(address = mpl_bubblegum::ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program, validated by bubblegum program
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: account compression program, validated by bubblegum program
+    pub compression_program: UncheckedAccount<'info>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ListCompressed<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn list(
+        &mut self,
+        asset_id: Pubkey,
+        leaf: CompressedLeaf,
+        metadata: &[u8],
+        price: u64,
+        decay: Option<DutchAuction>,
+        proof: &[AccountInfo<'info>],
+        bumps: &ListCompressedBumps,
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require_keys_eq!(
+            leaf.asset_id(&self.merkle_tree.key()),
+            asset_id,
+            MarketplaceErrors::AssetIdMismatch
+        );
+
+        // bubblegum checks `data_hash` against the tree on transfer, so the metadata behind it can be trusted
+        let collection = leaf.verified_collection(metadata)?;
+        require_keys_eq!(
+            collection,
+            self.collection_config.collection_mint,
+            MarketplaceErrors::CollectionMismatch
+        );
+        self.collection_config.validate_trading()?;
+
+        if let Some(decay) = decay {
+            decay.validate(price)?;
+        }
+
+        // transfer leaf ownership to the listing PDA
+        self.deposit_leaf(&leaf, proof)?;
+
+        self.listing.set_inner(Offer {
+            seller: (*self.seller.key),
+            price: (price),
+            decay: (decay),
+            expires_at: (None), // only vault listings can be expired by the crank
+            collection: (collection),
+            allowed_buyer: (None),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            referrer: (self.referrer.as_ref().map(|referrer| referrer.wallet)),
+            referrer_share: (self.referrer.as_ref().map_or(0, |referrer| referrer.share)),
+            custody: (Custody::Compressed),
+            bump: (bumps.listing),
+        });
+
+        self.listing.emit_listed(asset_id)?;
+
+        Ok(())
+    }
+
+    pub fn deposit_leaf(
+        &mut self,
+        leaf: &CompressedLeaf,
+        proof: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let seller = self.seller.to_account_info();
+        let proof: Vec<_> = proof.iter().map(|node| (node, false, false)).collect();
+
+        TransferCpi::new(
+            &self.bubblegum_program.to_account_info(),
+            TransferCpiAccounts {
+                tree_config: &self.tree_config.to_account_info(),
+                leaf_owner: (&seller, true),
+                leaf_delegate: (&seller, false),
+                new_leaf_owner: &self.listing.to_account_info(),
+                merkle_tree: &self.merkle_tree.to_account_info(),
+                log_wrapper: &self.log_wrapper.to_account_info(),
+                compression_program: &self.compression_program.to_account_info(),
+                system_program: &self.system_program.to_account_info(),
+            },
+            leaf.transfer_args(),
+        )
+        .invoke_with_remaining_accounts(&proof)?;
+
+        Ok(())
+    }
+}
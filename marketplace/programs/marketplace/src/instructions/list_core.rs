@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use mpl_core::{
+    accounts::BaseAssetV1,
+    instructions::{TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs},
+    types::UpdateAuthority,
+};
+
+use crate::{
+    error::MarketplaceErrors, CollectionConfig, Custody, DutchAuction, Global, Offer, Referrer,
+};
+
+#[derive(Accounts)]
+pub struct ListCore<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: core asset, owner checked here and deserialized in the handler
+    #[account(mut, owner = mpl_core::ID)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: core collection of the asset, validated by core program
+    #[account(mut)]
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"listing", seller.key().as_ref(), asset.key().as_ref()],
+        bump,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", collection_config.collection_mint.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    // partner frontend the nft is listed through, its share is snapshotted into the listing
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    /// CHECK: core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ListCore<'info> {
+    pub fn list(
+        &mut self,
+        price: u64,
+        decay: Option<DutchAuction>,
+        bumps: &ListCoreBumps,
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        // core collections are verified by construction, the asset only names one it belongs to
+        let collection = self.asset_collection()?;
+        require_keys_eq!(
+            collection,
+            self.collection_config.collection_mint,
+            MarketplaceErrors::CollectionMismatch
+        );
+        self.collection_config.validate_trading()?;
+
+        if let Some(decay) = decay {
+            decay.validate(price)?;
+        }
+
+        // transfer asset to the listing PDA, same custody model as `vault`
+        self.deposit_asset()?;
+
+        self.listing.set_inner(Offer {
+            seller: (*self.seller.key),
+            price: (price),
+            decay: (decay),
+            expires_at: (None), // only vault listings can be expired by the crank
+            collection: (collection),
+            allowed_buyer: (None),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            referrer: (self.referrer.as_ref().map(|referrer| referrer.wallet)),
+            referrer_share: (self.referrer.as_ref().map_or(0, |referrer| referrer.share)),
+            custody: (Custody::Core),
+            bump: (bumps.listing),
+        });
+
+        self.listing.emit_listed(self.asset.key())?;
+
+        Ok(())
+    }
+
+    /// core collection of the asset, assets outside of a collection can't be listed
+    pub fn asset_collection(&self) -> Result<Pubkey> {
+        let asset = BaseAssetV1::from_bytes(&self.asset.try_borrow_data()?)
+            .map_err(|_| MarketplaceErrors::InvalidAsset)?;
+
+        match asset.update_authority {
+            UpdateAuthority::Collection(collection) => Ok(collection),
+            _ => err!(MarketplaceErrors::UnverifiedCollection),
+        }
+    }
+
+    pub fn deposit_asset(&mut self) -> Result<()> {
+        let seller = self.seller.to_account_info();
+        let collection = self.collection.as_ref().map(|c| c.to_account_info());
+
+        TransferV1Cpi::new(
+            &self.mpl_core_program.to_account_info(),
+            TransferV1CpiAccounts {
+                asset: &self.asset.to_account_info(),
+                collection: collection.as_ref(),
+                payer: &seller,
+                authority: Some(&seller),
+                new_owner: &self.listing.to_account_info(),
+                system_program: Some(&self.system_program.to_account_info()),
+                log_wrapper: None,
+            },
+            TransferV1InstructionArgs {
+                compression_proof: None,
+            },
+        )
+        .invoke()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::instructions::{
+            FreezeDelegatedAccountCpi, FreezeDelegatedAccountCpiAccounts,
+        },
+        MasterEditionAccount, Metadata, MetadataAccount,
+    },
+    token::{approve, Approve, Mint, Token, TokenAccount},
+};
+
+use crate::{
+    error::MarketplaceErrors, CollectionConfig, Custody, DutchAuction, Global, Offer, Referrer,
+};
+
+#[derive(Accounts)]
+pub struct ListDelegated<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    // nft stays here, delegated to the listing and frozen
+    #[account(
+        mut,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // partner frontend the nft is listed through, its share is snapshotted into the listing
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(
+        seeds = [b"collection", collection_mint.key().as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> ListDelegated<'info> {
+    pub fn list(
+        &mut self,
+        price: u64,
+        decay: Option<DutchAuction>,
+        bumps: &ListDelegatedBumps,
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        self.collection_config.validate_member(&self.metadata)?;
+        self.collection_config.validate_trading()?;
+
+        if let Some(decay) = decay {
+            decay.validate(price)?;
+        }
+
+        self.listing.set_inner(Offer {
+            seller: (*self.seller.key),
+            price: (price),
+            decay: (decay),
+            expires_at: (None), // only vault listings can be expired by the crank
+            collection: (self.collection_mint.key()),
+            allowed_buyer: (None),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            referrer: (self.referrer.as_ref().map(|referrer| referrer.wallet)),
+            referrer_share: (self.referrer.as_ref().map_or(0, |referrer| referrer.share)),
+            custody: (Custody::Delegated),
+            bump: (bumps.listing),
+        });
+
+        self.listing.emit_listed(self.mint.key())?;
+
+        // delegate nft to the listing and freeze it in seller's wallet
+        self.freeze_nft()
+    }
+
+    pub fn freeze_nft(&mut self) -> Result<()> {
+        let delegate = self.listing.to_account_info();
+        let token_account = self.seller_ata.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        approve(
+            CpiContext::new(
+                token_program.clone(),
+                Approve {
+                    to: token_account.clone(),
+                    delegate: delegate.clone(),
+                    authority: self.seller.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        FreezeDelegatedAccountCpi::new(
+            &self.metadata_program.to_account_info(),
+            FreezeDelegatedAccountCpiAccounts {
+                delegate: &delegate,
+                token_account: &token_account,
+                edition: &self.edition.to_account_info(),
+                mint: &self.mint.to_account_info(),
+                token_program: &token_program,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
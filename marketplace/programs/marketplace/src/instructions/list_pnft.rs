@@ -0,0 +1,220 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        mpl_token_metadata::{
+            instructions::{TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs},
+            types::TokenStandard,
+        },
+        MasterEditionAccount, Metadata, MetadataAccount,
+    },
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::{
+    error::MarketplaceErrors, CollectionConfig, Custody, DutchAuction, Global, Offer, Referrer,
+};
+
+#[derive(Accounts)]
+pub struct ListPnft<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by seeds and token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            seller_ata.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub seller_token_record: UncheckedAccount<'info>,
+
+    // pnfts can only live in associated token accounts, so the listing PDA owns the escrow instead of `vault`
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::authority = listing,
+        associated_token::mint = mint,
+    )]
+    pub listing_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by seeds, created by token metadata program on transfer
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            listing_ata.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub listing_token_record: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // partner frontend the nft is listed through, its share is snapshotted into the listing
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(
+        seeds = [b"collection", collection_mint.key().as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    /// CHECK: rule set program, validated by token metadata program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: rule set of the pnft, validated by token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ListPnft<'info> {
+    pub fn list(
+        &mut self,
+        price: u64,
+        decay: Option<DutchAuction>,
+        bumps: &ListPnftBumps,
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require!(
+            self.metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible),
+            MarketplaceErrors::NotProgrammable
+        );
+
+        self.collection_config.validate_member(&self.metadata)?;
+        self.collection_config.validate_trading()?;
+
+        if let Some(decay) = decay {
+            decay.validate(price)?;
+        }
+
+        // transfer pnft to the listing escrow, seller signs as owner
+        self.deposit_pnft()?;
+
+        self.listing.set_inner(Offer {
+            seller: (*self.seller.key),
+            price: (price),
+            decay: (decay),
+            expires_at: (None), // only vault listings can be expired by the crank
+            collection: (self.collection_mint.key()),
+            allowed_buyer: (None),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            referrer: (self.referrer.as_ref().map(|referrer| referrer.wallet)),
+            referrer_share: (self.referrer.as_ref().map_or(0, |referrer| referrer.share)),
+            custody: (Custody::Programmable),
+            bump: (bumps.listing),
+        });
+
+        self.listing.emit_listed(self.mint.key())?;
+
+        Ok(())
+    }
+
+    pub fn deposit_pnft(&mut self) -> Result<()> {
+        let seller = self.seller.to_account_info();
+        let listing = self.listing.to_account_info();
+
+        TransferV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            TransferV1CpiAccounts {
+                token: &self.seller_ata.to_account_info(),
+                token_owner: &seller,
+                destination_token: &self.listing_ata.to_account_info(),
+                destination_owner: &listing,
+                mint: &self.mint.to_account_info(),
+                metadata: &self.metadata.to_account_info(),
+                edition: Some(&self.edition.to_account_info()),
+                token_record: Some(&self.seller_token_record.to_account_info()),
+                destination_token_record: Some(&self.listing_token_record.to_account_info()),
+                authority: &seller,
+                payer: &seller,
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instructions.to_account_info(),
+                spl_token_program: &self.token_program.to_account_info(),
+                spl_ata_program: &self.associated_token_program.to_account_info(),
+                authorization_rules_program: self
+                    .authorization_rules_program
+                    .as_ref()
+                    .map(|program| program.as_ref()),
+                authorization_rules: self
+                    .authorization_rules
+                    .as_ref()
+                    .map(|rules| rules.as_ref()),
+            },
+            TransferV1InstructionArgs {
+                amount: 1,
+                authorization_data: None,
+            },
+        )
+        .invoke()?;
+
+        Ok(())
+    }
+}
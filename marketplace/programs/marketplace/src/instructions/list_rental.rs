@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, Global, Rental};
+
+#[derive(Accounts)]
+pub struct ListRental<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::authority = owner,
+        associated_token::mint = mint,
+    )]
+    pub owner_ata: Account<'info, TokenAccount>,
+
+    // nft waits here between listing and the first rent
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Rental::INIT_SPACE,
+        seeds = [b"rental", owner.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub rental: Account<'info, Rental>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", collection_mint.key().as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ListRental<'info> {
+    pub fn list(&mut self, price_per_day: u64, days: u16, bumps: &ListRentalBumps) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        self.collection_config.validate_member(&self.metadata)?;
+        self.collection_config.validate_trading()?;
+
+        require!(
+            price_per_day > 0 && days > 0,
+            MarketplaceErrors::InvalidRental
+        );
+
+        self.rental.set_inner(Rental {
+            owner: (*self.owner.key),
+            mint: (self.mint.key()),
+            collection: (self.collection_mint.key()),
+            price_per_day: (price_per_day),
+            days: (days),
+            fee: (self.collection_config.effective_fee(&self.global)),
+            renter: (None),
+            rented_until: (0),
+            bump: (bumps.rental),
+        });
+
+        self.rental.emit_listed()?;
+
+        // same custody as fixed price listings until the nft is rented
+        self.deposit_nft()
+    }
+
+    pub fn deposit_nft(&mut self) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.owner_ata.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.owner.to_account_info(),
+                },
+            ),
+            1,
+        )
+    }
+}
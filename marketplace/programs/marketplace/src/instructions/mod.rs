@@ -7,11 +7,125 @@ pub use list::*;
 pub mod delist;
 pub use delist::*;
 
+pub mod update_listing;
+pub use update_listing::*;
+
+pub mod expire_listing;
+pub use expire_listing::*;
+
 pub mod purchase;
 pub use purchase::*;
 
+pub mod batch_list;
+pub use batch_list::*;
+
+pub mod batch_delist;
+pub use batch_delist::*;
+
+pub mod sweep;
+pub use sweep::*;
+
+pub mod list_delegated;
+pub use list_delegated::*;
+
+pub mod delist_delegated;
+pub use delist_delegated::*;
+
+pub mod purchase_delegated;
+pub use purchase_delegated::*;
+
+pub mod list_pnft;
+pub use list_pnft::*;
+
+pub mod delist_pnft;
+pub use delist_pnft::*;
+
+pub mod purchase_pnft;
+pub use purchase_pnft::*;
+
+pub mod list_core;
+pub use list_core::*;
+
+pub mod delist_core;
+pub use delist_core::*;
+
+pub mod purchase_core;
+pub use purchase_core::*;
+
+pub mod list_compressed;
+pub use list_compressed::*;
+
+pub mod delist_compressed;
+pub use delist_compressed::*;
+
+pub mod purchase_compressed;
+pub use purchase_compressed::*;
+
+pub mod list_rental;
+pub use list_rental::*;
+
+pub mod delist_rental;
+pub use delist_rental::*;
+
+pub mod rent_nft;
+pub use rent_nft::*;
+
+pub mod reclaim;
+pub use reclaim::*;
+
+pub mod propose_trade;
+pub use propose_trade::*;
+
+pub mod cancel_trade;
+pub use cancel_trade::*;
+
+pub mod deposit_trade;
+pub use deposit_trade::*;
+
+pub mod withdraw_trade;
+pub use withdraw_trade::*;
+
+pub mod accept_trade;
+pub use accept_trade::*;
+
 pub mod freeze_thaw;
 pub use freeze_thaw::*;
 
-pub mod update_fee;
-pub use update_fee::*;
+pub mod propose_action;
+pub use propose_action::*;
+
+pub mod execute_action;
+pub use execute_action::*;
+
+pub mod cancel_action;
+pub use cancel_action::*;
+
+pub mod get_fees;
+pub use get_fees::*;
+
+pub mod set_collection;
+pub use set_collection::*;
+
+pub mod set_referrer;
+pub use set_referrer::*;
+
+pub mod place_bid;
+pub use place_bid::*;
+
+pub mod cancel_bid;
+pub use cancel_bid::*;
+
+pub mod accept_bid;
+pub use accept_bid::*;
+
+pub mod create_auction;
+pub use create_auction::*;
+
+pub mod auction_bid;
+pub use auction_bid::*;
+
+pub mod settle_auction;
+pub use settle_auction::*;
+
+pub mod refund_auction;
+pub use refund_auction::*;
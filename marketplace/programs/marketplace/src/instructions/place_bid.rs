@@ -0,0 +1,75 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::Mint;
+
+use crate::{error::MarketplaceErrors, Bid, BidPlaced, Global};
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    // nft mint, or collection mint for collection-wide offers
+    pub target: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + Bid::INIT_SPACE,
+        seeds = [b"bid", bidder.key().as_ref(), target.key().as_ref()],
+        bump,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> PlaceBid<'info> {
+    pub fn place(&mut self, price: u64, collection: bool, bumps: &PlaceBidBumps) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require!(price > 0, MarketplaceErrors::ZeroBid);
+
+        self.bid.set_inner(Bid {
+            bidder: (*self.bidder.key),
+            target: (self.target.key()),
+            collection: (collection),
+            price: (price),
+            bump: (bumps.bid),
+        });
+
+        // escrow bid lamports in the bid account itself
+        self.deposit_sol(price)?;
+
+        emit!(BidPlaced {
+            bidder: self.bid.bidder,
+            target: self.bid.target,
+            collection,
+            price,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_sol(&mut self, price: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.bidder.to_account_info(),
+                    to: self.bid.to_account_info(),
+                },
+            ),
+            price,
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    error::MarketplaceErrors, ActionProposed, AdminAction, FeeUpdated, Global, QueuedAction,
+    Referrer,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAction<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"global"],
+        bump = global.bump,
+        has_one = admin,
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + QueuedAction::INIT_SPACE,
+        seeds = [b"action", global.action_count.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub queued_action: Account<'info, QueuedAction>,
+
+    // cpi programs
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeAction<'info> {
+    pub fn propose(&mut self, action: AdminAction, bumps: &ProposeActionBumps) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let executable_at = now + action.delay();
+        let id = self.global.action_count;
+
+        match action {
+            AdminAction::UpdateFee { fee } => self.propose_fee(fee, id, now, executable_at)?,
+            // collection fee is bound by the same 0 - 0.5% range as protocol fee
+            AdminAction::SetCollectionFee { fee: Some(fee), .. } => {
+                require!(fee <= 50, MarketplaceErrors::MaxFee)
+            }
+            AdminAction::SetReferrerShare { share, .. } => require!(
+                share <= Referrer::MAX_SHARE,
+                MarketplaceErrors::MaxReferralShare
+            ),
+            _ => {}
+        }
+
+        self.global.action_count += 1;
+
+        self.queued_action.set_inner(QueuedAction {
+            id: (id),
+            action: (action),
+            proposer: (*self.admin.key),
+            proposed_at: (now),
+            executable_at: (executable_at),
+            bump: (bumps.queued_action),
+        });
+
+        emit!(ActionProposed {
+            id,
+            action,
+            executable_at,
+            timestamp: now,
+        });
+
+        Ok(())
+    }
+
+    pub fn propose_fee(
+        &mut self,
+        new_fee: u16,
+        id: u64,
+        now: i64,
+        executable_at: i64,
+    ) -> Result<()> {
+        // only one fee update can be queued at a time, so `get_fees` always shows the upcoming fee
+        require!(
+            self.global.fee_action.is_none(),
+            MarketplaceErrors::FeeUpdatePending
+        );
+
+        // protocol fee can range only between 0 - 0.5%
+        require!(new_fee <= 50, MarketplaceErrors::MaxFee);
+
+        // the queued action holds the pending fee, global only points at it
+        self.global.fee_action = Some(id);
+
+        emit!(FeeUpdated {
+            fee: self.global.fee,
+            new_fee,
+            effective_at: executable_at,
+            timestamp: now,
+        });
+
+        msg!(
+            "Fee change from {:?} to {:?} after 2 week period initiated",
+            self.global.fee,
+            new_fee
+        );
+        Ok(())
+    }
+}
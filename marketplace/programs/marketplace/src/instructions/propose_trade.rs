@@ -0,0 +1,125 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer as transfer_sol, Transfer as TransferSol},
+};
+use anchor_spl::token::Token;
+
+use crate::{error::MarketplaceErrors, events::TradeProposed, Global, Trade};
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct ProposeTrade<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    pub taker: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + Trade::INIT_SPACE,
+        seeds = [b"trade", maker.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub trade: Account<'info, Trade>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeTrade<'info> {
+    /// remaining accounts per offered nft: [mint, maker_ata, vault, metadata, collection_config]
+    pub const ACCOUNTS_PER_NFT: usize = 5;
+
+    pub fn propose(
+        &mut self,
+        id: u64,
+        requested: Vec<Pubkey>,
+        lamports: u64,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        bumps: &ProposeTradeBumps,
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require!(
+            remaining_accounts
+                .chunks_exact(Self::ACCOUNTS_PER_NFT)
+                .remainder()
+                .is_empty(),
+            MarketplaceErrors::InvalidTradeAccounts
+        );
+
+        let offered: Vec<Pubkey> = remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_NFT)
+            .map(|accounts| accounts[0].key())
+            .collect();
+        Trade::validate_size(offered.len(), requested.len())?;
+
+        self.trade.set_inner(Trade {
+            maker: (*self.maker.key),
+            taker: (*self.taker.key),
+            id: (id),
+            offered: (offered),
+            requested: (requested),
+            lamports: (lamports),
+            taker_deposited: (false),
+            collections: (Vec::new()),
+            bump: (bumps.trade),
+        });
+
+        // any failing nft reverts the whole trade
+        for accounts in remaining_accounts.chunks(Self::ACCOUNTS_PER_NFT) {
+            self.deposit_nft(accounts)?;
+        }
+
+        if lamports > 0 {
+            transfer_sol(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    TransferSol {
+                        from: self.maker.to_account_info(),
+                        to: self.trade.to_account_info(),
+                    },
+                ),
+                lamports,
+            )?;
+        }
+
+        emit!(TradeProposed {
+            maker: self.trade.maker,
+            taker: self.trade.taker,
+            id,
+            offered: self.trade.offered.clone(),
+            requested: self.trade.requested.clone(),
+            lamports,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_nft(&mut self, accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let [mint, maker_ata, vault, metadata, collection_config] = accounts else {
+            return err!(MarketplaceErrors::InvalidTradeAccounts);
+        };
+
+        // same collection rules as listing, the nft has to be tradeable here
+        let collection = Trade::validate_collection(mint.key(), metadata, collection_config)?;
+        self.trade.add_collection(collection);
+
+        Trade::deposit_nft(
+            &self.maker.to_account_info(),
+            mint,
+            maker_ata,
+            vault,
+            &self.token_program.to_account_info(),
+            &self.system_program.to_account_info(),
+        )
+    }
+}
@@ -5,11 +5,14 @@ use anchor_lang::{
 
 use anchor_spl::{
     associated_token::AssociatedToken,
-    metadata::{Metadata, MetadataAccount},
-    token::{transfer as transfer_nft, Mint, Token, TokenAccount, Transfer as TransferNft},
+    metadata::Metadata,
+    token::{
+        close_account, transfer as transfer_nft, CloseAccount, Mint, Token, TokenAccount,
+        Transfer as TransferNft,
+    },
 };
 
-use crate::{error::MarketplaceErrors, Global, Offer};
+use crate::{error::MarketplaceErrors, CollectionConfig, Custody, Global, Offer, Referrer};
 
 #[derive(Accounts)]
 pub struct Purchase<'info> {
@@ -42,7 +45,6 @@ pub struct Purchase<'info> {
         bump,
         token::authority = vault,
         token::mint = mint,
-        close = seller,
     )]
     pub vault: Account<'info, TokenAccount>,
 
@@ -60,9 +62,25 @@ pub struct Purchase<'info> {
     )]
     pub global: Account<'info, Global>,
 
+    #[account(
+        seeds = [b"collection", listing.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
     #[account(mut, address = global.treasury)] //@audit :: validate whether this prevents bypass ?
     pub treasury: SystemAccount<'info>, //@audit :: figure out a better way to convert raw Pubkey to type of AccountInfo<'_> // or maybe you can store hashmap type thing which stores address-> T or some raw bytes magic ..... for the later !!!
 
+    // partner frontend the purchase is made through, paid its share of the fee
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_wallet: Option<SystemAccount<'info>>,
+
+    // partner frontend the nft was listed through, required if the listing has a referrer
+    #[account(mut)]
+    pub listing_referrer: Option<SystemAccount<'info>>,
+
     // cpi programs
     token_program: Program<'info, Token>,
     system_program: Program<'info, System>,
@@ -76,41 +94,52 @@ pub struct Purchase<'info> {
 // we send nft to buyer
 
 impl<'info> Purchase<'info> {
-    pub fn purchase(&mut self, bumps: &PurchaseBumps) -> Result<()> {
+    pub fn purchase(&mut self, max_price: u64, bumps: &PurchaseBumps) -> Result<()> {
         require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        // other custodies are sold through their own purchase instruction
+        require!(
+            self.listing.custody == Custody::Vault,
+            MarketplaceErrors::CustodyNotSupported
+        );
+        require!(
+            !self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceErrors::ListingExpired
+        );
+        self.listing.validate_buyer(self.buyer.key)?;
+        self.collection_config.validate_trading()?;
 
         // pay sol price to seller and cut protocol fee
-        self.pay_sol()?;
+        self.pay_sol(max_price)?;
 
         // close listing and transfer nft to the buyer
         self.transfer_nft(bumps)
     }
 
-    pub fn pay_sol(&mut self) -> Result<()> {
+    pub fn pay_sol(&mut self, max_price: u64) -> Result<()> {
         let system = self.system_program.to_account_info();
         let treasury = self.treasury.to_account_info();
         let buyer = self.buyer.to_account_info();
         let seller = self.seller.to_account_info();
-        let price = self.listing.price;
-
         // Fee calculations
         let now = Clock::get()?.unix_timestamp;
-        const TWO_WEEKS: i64 = 2 * 7 * 24 * 60 * 60; // 1,209,600 seconds
-
-        // if 2 weeks have passed after admin had set new_fee, then start charging the new_fee, otherwise keep the old one
-        let bips = if ((now - self.global.new_fee_at >= TWO_WEEKS) && (self.global.new_fee > 0)) {
-            self.global.fee = self.global.new_fee;
-            self.global.new_fee = 0;
-            self.global.new_fee_at = 0;
+        let price = self.listing.price_for(now, max_price)?;
 
-            self.global.fee as u64
-        } else {
-            self.global.fee as u64
-        };
+        // fee snapshotted at listing time, later fee changes don't affect this listing
+        let bips = self.listing.fee as u64;
 
         let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
         let price_minus_fee = price - fee;
 
+        // referrers are paid out of the protocol fee, treasury keeps the rest
+        let referral = self.listing.pay_referrers(
+            fee,
+            &buyer,
+            &system,
+            self.listing_referrer.as_ref().map(|wallet| wallet.as_ref()),
+            self.referrer.as_deref(),
+            self.referrer_wallet.as_ref().map(|wallet| wallet.as_ref()),
+        )?;
+
         // pay nft price to seller
         transfer(
             CpiContext::new(
@@ -124,7 +153,7 @@ impl<'info> Purchase<'info> {
         )?;
 
         // charge fee on seller's price
-        if fee > 0 {
+        if fee - referral > 0 {
             transfer(
                 CpiContext::new(
                     system,
@@ -133,14 +162,20 @@ impl<'info> Purchase<'info> {
                         to: treasury,
                     },
                 ),
-                fee,
+                fee - referral,
             )?;
         }
 
+        self.listing
+            .emit_purchased(self.buyer.key(), self.mint.key(), price, fee)?;
+
         Ok(())
     }
 
     pub fn transfer_nft(&mut self, bumps: &PurchaseBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
         transfer_nft(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
@@ -149,9 +184,19 @@ impl<'info> Purchase<'info> {
                     to: self.buyer_ata.to_account_info(),
                     authority: self.vault.to_account_info(),
                 },
-                &[&[b"vault", self.mint.key().as_ref(), &[bumps.vault]]],
+                signer_seeds,
             ),
             1,
-        )
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
     }
 }
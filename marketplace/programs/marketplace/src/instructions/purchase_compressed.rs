@@ -0,0 +1,184 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use mpl_bubblegum::instructions::{TransferCpi, TransferCpiAccounts};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, CompressedLeaf, Global, Offer, Referrer};
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct PurchaseCompressed<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+
+    /// CHECK: bubblegum tree config, validated by bubblegum program
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// CHECK: merkle tree holding the leaf, validated by account compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), asset_id.as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", listing.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // partner frontend the purchase is made through, paid its share of the fee
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_wallet: Option<SystemAccount<'info>>,
+
+    // partner frontend the nft was listed through, required if the listing has a referrer
+    #[account(mut)]
+    pub listing_referrer: Option<SystemAccount<'info>>,
+
+    /// CHECK: bubblegum program
+    #[account(address = mpl_bubblegum::ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: noop program, validated by bubblegum program
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: account compression program, validated by bubblegum program
+    pub compression_program: UncheckedAccount<'info>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> PurchaseCompressed<'info> {
+    pub fn purchase(
+        &mut self,
+        asset_id: Pubkey,
+        leaf: CompressedLeaf,
+        max_price: u64,
+        proof: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require_keys_eq!(
+            leaf.asset_id(&self.merkle_tree.key()),
+            asset_id,
+            MarketplaceErrors::AssetIdMismatch
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !self.listing.is_expired(now),
+            MarketplaceErrors::ListingExpired
+        );
+        self.listing.validate_buyer(self.buyer.key)?;
+        self.collection_config.validate_trading()?;
+
+        // pay sol price to seller and cut protocol fee
+        self.pay_sol(asset_id, now, max_price)?;
+
+        // close listing and transfer the leaf to the buyer
+        self.transfer_leaf(asset_id, &leaf, proof)
+    }
+
+    pub fn pay_sol(&mut self, asset_id: Pubkey, now: i64, max_price: u64) -> Result<()> {
+        let price = self.listing.price_for(now, max_price)?;
+        let bips = self.listing.fee as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        // referrers are paid out of the protocol fee, treasury keeps the rest
+        let referral = self.listing.pay_referrers(
+            fee,
+            &self.buyer.to_account_info(),
+            &self.system_program.to_account_info(),
+            self.listing_referrer.as_ref().map(|wallet| wallet.as_ref()),
+            self.referrer.as_deref(),
+            self.referrer_wallet.as_ref().map(|wallet| wallet.as_ref()),
+        )?;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.buyer.to_account_info(),
+                    to: self.seller.to_account_info(),
+                },
+            ),
+            price_minus_fee,
+        )?;
+
+        if fee - referral > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.buyer.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                fee - referral,
+            )?;
+        }
+
+        self.listing
+            .emit_purchased(self.buyer.key(), asset_id, price, fee)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_leaf(
+        &mut self,
+        asset_id: Pubkey,
+        leaf: &CompressedLeaf,
+        proof: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let listing = self.listing.to_account_info();
+        let proof: Vec<_> = proof.iter().map(|node| (node, false, false)).collect();
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            asset_id.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        TransferCpi::new(
+            &self.bubblegum_program.to_account_info(),
+            TransferCpiAccounts {
+                tree_config: &self.tree_config.to_account_info(),
+                leaf_owner: (&listing, true),
+                leaf_delegate: (&listing, false),
+                new_leaf_owner: &self.buyer.to_account_info(),
+                merkle_tree: &self.merkle_tree.to_account_info(),
+                log_wrapper: &self.log_wrapper.to_account_info(),
+                compression_program: &self.compression_program.to_account_info(),
+                system_program: &self.system_program.to_account_info(),
+            },
+            leaf.transfer_args(),
+        )
+        .invoke_signed_with_remaining_accounts(signer_seeds, &proof)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use mpl_core::instructions::{TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, Global, Offer, Referrer};
+
+#[derive(Accounts)]
+pub struct PurchaseCore<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+
+    /// CHECK: core asset, validated by core program
+    #[account(mut, owner = mpl_core::ID)]
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: core collection of the asset, validated by core program
+    #[account(mut)]
+    pub collection: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), asset.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", listing.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // partner frontend the purchase is made through, paid its share of the fee
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_wallet: Option<SystemAccount<'info>>,
+
+    // partner frontend the nft was listed through, required if the listing has a referrer
+    #[account(mut)]
+    pub listing_referrer: Option<SystemAccount<'info>>,
+
+    /// CHECK: core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    // cpi programs
+    system_program: Program<'info, System>,
+}
+
+impl<'info> PurchaseCore<'info> {
+    pub fn purchase(&mut self, max_price: u64) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !self.listing.is_expired(now),
+            MarketplaceErrors::ListingExpired
+        );
+        self.listing.validate_buyer(self.buyer.key)?;
+        self.collection_config.validate_trading()?;
+
+        // pay sol price to seller and cut protocol fee
+        self.pay_sol(now, max_price)?;
+
+        // close listing and transfer asset to the buyer
+        self.transfer_asset()
+    }
+
+    pub fn pay_sol(&mut self, now: i64, max_price: u64) -> Result<()> {
+        let price = self.listing.price_for(now, max_price)?;
+        let bips = self.listing.fee as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        // referrers are paid out of the protocol fee, treasury keeps the rest
+        let referral = self.listing.pay_referrers(
+            fee,
+            &self.buyer.to_account_info(),
+            &self.system_program.to_account_info(),
+            self.listing_referrer.as_ref().map(|wallet| wallet.as_ref()),
+            self.referrer.as_deref(),
+            self.referrer_wallet.as_ref().map(|wallet| wallet.as_ref()),
+        )?;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.buyer.to_account_info(),
+                    to: self.seller.to_account_info(),
+                },
+            ),
+            price_minus_fee,
+        )?;
+
+        if fee - referral > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.buyer.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                fee - referral,
+            )?;
+        }
+
+        self.listing
+            .emit_purchased(self.buyer.key(), self.asset.key(), price, fee)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_asset(&mut self) -> Result<()> {
+        let listing = self.listing.to_account_info();
+        let collection = self.collection.as_ref().map(|c| c.to_account_info());
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let asset_key = self.asset.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            asset_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        TransferV1Cpi::new(
+            &self.mpl_core_program.to_account_info(),
+            TransferV1CpiAccounts {
+                asset: &self.asset.to_account_info(),
+                collection: collection.as_ref(),
+                payer: &self.buyer.to_account_info(),
+                authority: Some(&listing),
+                new_owner: &self.buyer.to_account_info(),
+                system_program: Some(&self.system_program.to_account_info()),
+                log_wrapper: None,
+            },
+            TransferV1InstructionArgs {
+                compression_proof: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        mpl_token_metadata::instructions::{
+            ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts,
+        },
+        MasterEditionAccount, Metadata,
+    },
+    token::{transfer as transfer_nft, Mint, Token, TokenAccount, Transfer as TransferNft},
+};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, Global, Offer, Referrer};
+
+#[derive(Accounts)]
+pub struct PurchaseDelegated<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::authority = buyer,
+        associated_token::mint = mint,
+    )]
+    pub buyer_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", listing.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // partner frontend the purchase is made through, paid its share of the fee
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_wallet: Option<SystemAccount<'info>>,
+
+    // partner frontend the nft was listed through, required if the listing has a referrer
+    #[account(mut)]
+    pub listing_referrer: Option<SystemAccount<'info>>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> PurchaseDelegated<'info> {
+    pub fn purchase(&mut self, max_price: u64) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !self.listing.is_expired(now),
+            MarketplaceErrors::ListingExpired
+        );
+        self.listing.validate_buyer(self.buyer.key)?;
+        self.collection_config.validate_trading()?;
+
+        // pay sol price to seller and cut protocol fee
+        self.pay_sol(now, max_price)?;
+
+        // thaw seller's nft and move it to the buyer as delegate
+        self.transfer_nft()
+    }
+
+    pub fn pay_sol(&mut self, now: i64, max_price: u64) -> Result<()> {
+        let price = self.listing.price_for(now, max_price)?;
+        let bips = self.listing.fee as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        // referrers are paid out of the protocol fee, treasury keeps the rest
+        let referral = self.listing.pay_referrers(
+            fee,
+            &self.buyer.to_account_info(),
+            &self.system_program.to_account_info(),
+            self.listing_referrer.as_ref().map(|wallet| wallet.as_ref()),
+            self.referrer.as_deref(),
+            self.referrer_wallet.as_ref().map(|wallet| wallet.as_ref()),
+        )?;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.buyer.to_account_info(),
+                    to: self.seller.to_account_info(),
+                },
+            ),
+            price_minus_fee,
+        )?;
+
+        if fee - referral > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.buyer.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                fee - referral,
+            )?;
+        }
+
+        self.listing
+            .emit_purchased(self.buyer.key(), self.mint.key(), price, fee)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_nft(&mut self) -> Result<()> {
+        let delegate = self.listing.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        ThawDelegatedAccountCpi::new(
+            &self.metadata_program.to_account_info(),
+            ThawDelegatedAccountCpiAccounts {
+                delegate: &delegate,
+                token_account: &self.seller_ata.to_account_info(),
+                edition: &self.edition.to_account_info(),
+                mint: &self.mint.to_account_info(),
+                token_program: &token_program,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        // delegation is used up by this transfer
+        transfer_nft(
+            CpiContext::new_with_signer(
+                token_program,
+                TransferNft {
+                    from: self.seller_ata.to_account_info(),
+                    to: self.buyer_ata.to_account_info(),
+                    authority: delegate,
+                },
+                signer_seeds,
+            ),
+            1,
+        )
+    }
+}
@@ -0,0 +1,273 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        mpl_token_metadata::instructions::{
+            TransferV1Cpi, TransferV1CpiAccounts, TransferV1InstructionArgs,
+        },
+        MasterEditionAccount, Metadata, MetadataAccount,
+    },
+    token::{close_account, CloseAccount, Mint, Token, TokenAccount},
+};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, Global, Offer, Referrer};
+
+#[derive(Accounts)]
+pub struct PurchasePnft<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::authority = buyer,
+        associated_token::mint = mint,
+    )]
+    pub buyer_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by seeds, created by token metadata program on transfer
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            buyer_ata.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub buyer_token_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::authority = listing,
+        associated_token::mint = mint,
+    )]
+    pub listing_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: validated by seeds and token metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"token_record",
+            listing_ata.key().as_ref(),
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub listing_token_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), mint.key().as_ref()],
+        bump = listing.bump,
+        close = seller,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", listing.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // partner frontend the purchase is made through, paid its share of the fee
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_wallet: Option<SystemAccount<'info>>,
+
+    // partner frontend the nft was listed through, required if the listing has a referrer
+    #[account(mut)]
+    pub listing_referrer: Option<SystemAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        seeds::program = metadata_program.key(),
+        bump
+    )]
+    pub metadata: Account<'info, MetadataAccount>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    /// CHECK: rule set program, validated by token metadata program
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: rule set of the pnft, validated by token metadata program
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> PurchasePnft<'info> {
+    pub fn purchase(&mut self, max_price: u64) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !self.listing.is_expired(now),
+            MarketplaceErrors::ListingExpired
+        );
+        self.listing.validate_buyer(self.buyer.key)?;
+        self.collection_config.validate_trading()?;
+
+        // pay sol price to seller and cut protocol fee
+        self.pay_sol(now, max_price)?;
+
+        // close listing and transfer pnft to the buyer
+        self.transfer_pnft()
+    }
+
+    pub fn pay_sol(&mut self, now: i64, max_price: u64) -> Result<()> {
+        let price = self.listing.price_for(now, max_price)?;
+
+        let bips = self.listing.fee as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        // referrers are paid out of the protocol fee, treasury keeps the rest
+        let referral = self.listing.pay_referrers(
+            fee,
+            &self.buyer.to_account_info(),
+            &self.system_program.to_account_info(),
+            self.listing_referrer.as_ref().map(|wallet| wallet.as_ref()),
+            self.referrer.as_deref(),
+            self.referrer_wallet.as_ref().map(|wallet| wallet.as_ref()),
+        )?;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.buyer.to_account_info(),
+                    to: self.seller.to_account_info(),
+                },
+            ),
+            price_minus_fee,
+        )?;
+
+        if fee - referral > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.buyer.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                fee - referral,
+            )?;
+        }
+
+        self.listing
+            .emit_purchased(self.buyer.key(), self.mint.key(), price, fee)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_pnft(&mut self) -> Result<()> {
+        let buyer = self.buyer.to_account_info();
+        let listing = self.listing.to_account_info();
+
+        let seller_key = self.seller.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"listing",
+            seller_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.listing.bump],
+        ]];
+
+        TransferV1Cpi::new(
+            &self.metadata_program.to_account_info(),
+            TransferV1CpiAccounts {
+                token: &self.listing_ata.to_account_info(),
+                token_owner: &listing,
+                destination_token: &self.buyer_ata.to_account_info(),
+                destination_owner: &buyer,
+                mint: &self.mint.to_account_info(),
+                metadata: &self.metadata.to_account_info(),
+                edition: Some(&self.edition.to_account_info()),
+                token_record: Some(&self.listing_token_record.to_account_info()),
+                destination_token_record: Some(&self.buyer_token_record.to_account_info()),
+                authority: &listing,
+                payer: &buyer,
+                system_program: &self.system_program.to_account_info(),
+                sysvar_instructions: &self.sysvar_instructions.to_account_info(),
+                spl_token_program: &self.token_program.to_account_info(),
+                spl_ata_program: &self.associated_token_program.to_account_info(),
+                authorization_rules_program: self
+                    .authorization_rules_program
+                    .as_ref()
+                    .map(|program| program.as_ref()),
+                authorization_rules: self
+                    .authorization_rules
+                    .as_ref()
+                    .map(|rules| rules.as_ref()),
+            },
+            TransferV1InstructionArgs {
+                amount: 1,
+                authorization_data: None,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        // emptied listing ata rent goes back to the seller who paid for it
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.listing_ata.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: listing,
+            },
+            signer_seeds,
+        ))
+    }
+}
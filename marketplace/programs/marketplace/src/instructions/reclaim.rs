@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        mpl_token_metadata::instructions::{
+            ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts,
+        },
+        MasterEditionAccount, Metadata,
+    },
+    token::{transfer, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Global, Rental};
+
+#[derive(Accounts)]
+pub struct Reclaim<'info> {
+    // anyone can crank finished rentals
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = rental.owner)]
+    pub owner: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::authority = owner,
+        associated_token::mint = mint,
+    )]
+    pub owner_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        constraint = rental.renter == Some(renter_ata.owner) @ MarketplaceErrors::NotRented,
+    )]
+    pub renter_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"rental", owner.key().as_ref(), mint.key().as_ref()],
+        bump = rental.bump,
+        close = owner,
+    )]
+    pub rental: Account<'info, Rental>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> Reclaim<'info> {
+    pub fn reclaim(&mut self) -> Result<()> {
+        require!(
+            self.rental.is_over(Clock::get()?.unix_timestamp),
+            MarketplaceErrors::RentalNotOver
+        );
+
+        // same as delisting, nothing leaves a frozen protocol before 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        self.rental.emit_reclaimed(self.renter_ata.owner)?;
+
+        // thaw renter's nft and move it back to the owner as delegate
+        self.return_nft()
+    }
+
+    pub fn return_nft(&mut self) -> Result<()> {
+        let delegate = self.rental.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        let owner_key = self.owner.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"rental",
+            owner_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.rental.bump],
+        ]];
+
+        ThawDelegatedAccountCpi::new(
+            &self.metadata_program.to_account_info(),
+            ThawDelegatedAccountCpiAccounts {
+                delegate: &delegate,
+                token_account: &self.renter_ata.to_account_info(),
+                edition: &self.edition.to_account_info(),
+                mint: &self.mint.to_account_info(),
+                token_program: &token_program,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        // delegation is used up by this transfer
+        transfer(
+            CpiContext::new_with_signer(
+                token_program,
+                Transfer {
+                    from: self.renter_ata.to_account_info(),
+                    to: self.owner_ata.to_account_info(),
+                    authority: delegate,
+                },
+                signer_seeds,
+            ),
+            1,
+        )
+    }
+}
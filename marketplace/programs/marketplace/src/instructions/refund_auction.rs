@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{error::MarketplaceErrors, Auction, AuctionClosed, CollectionConfig, Global};
+
+#[derive(Accounts)]
+pub struct RefundAuction<'info> {
+    // anyone can unwind an auction stuck behind a frozen protocol or a halted collection
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = auction.seller)]
+    pub seller: SystemAccount<'info>,
+
+    // highest bidder getting the escrowed bid back, None when nobody bid
+    #[account(
+        mut,
+        address = auction.highest_bidder,
+    )]
+    pub highest_bidder: Option<SystemAccount<'info>>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::authority = seller,
+        associated_token::mint = mint,
+    )]
+    pub seller_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", seller.key().as_ref(), mint.key().as_ref()],
+        bump = auction.bump,
+        close = seller,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", auction.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> RefundAuction<'info> {
+    pub fn refund(&mut self, bumps: &RefundAuctionBumps) -> Result<()> {
+        // settling sales is disallowed while frozen, this is the only way out for both sides
+        // a disabled or frozen collection blocks settling the same way, without the protocol delay
+        if self.collection_config.validate_trading().is_ok() {
+            require!(self.global.frozen, MarketplaceErrors::ProtocolNotFrozen);
+            self.global.check_frozen_withdrawal()?;
+        }
+
+        self.refund_highest_bidder()?;
+
+        emit!(AuctionClosed {
+            seller: self.auction.seller,
+            mint: self.auction.mint,
+            collection: self.auction.collection,
+            refunded_bidder: self.highest_bidder.as_ref().map(|bidder| bidder.key()),
+            refund: self.auction.highest_bid,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // nft goes back to the seller and the vault is closed, auction rent goes back to seller
+        self.withdraw_nft(bumps)
+    }
+
+    pub fn refund_highest_bidder(&mut self) -> Result<()> {
+        let refund = self.auction.highest_bid;
+        if refund == 0 {
+            return Ok(());
+        }
+
+        let highest_bidder = self
+            .highest_bidder
+            .as_ref()
+            .ok_or(MarketplaceErrors::MissingPreviousBidder)?;
+
+        // auction account is owned by this program, so lamports can be moved out directly
+        self.auction.sub_lamports(refund)?;
+        highest_bidder.add_lamports(refund)?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_nft(&mut self, bumps: &RefundAuctionBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.seller_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
@@ -0,0 +1,216 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{
+        mpl_token_metadata::instructions::{
+            FreezeDelegatedAccountCpi, FreezeDelegatedAccountCpiAccounts,
+        },
+        MasterEditionAccount, Metadata,
+    },
+    token::{
+        approve, close_account, transfer as transfer_nft, Approve, CloseAccount, Mint, Token,
+        TokenAccount, Transfer as TransferNft,
+    },
+};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, Global, Rental};
+
+#[derive(Accounts)]
+pub struct RentNft<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    #[account(mut, address = rental.owner)]
+    pub owner: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    // nft stays here for the rental, delegated to the rental and frozen
+    #[account(
+        init_if_needed,
+        payer = renter,
+        associated_token::authority = renter,
+        associated_token::mint = mint,
+    )]
+    pub renter_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"rental", owner.key().as_ref(), mint.key().as_ref()],
+        bump = rental.bump,
+    )]
+    pub rental: Account<'info, Rental>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", rental.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"metadata",
+            metadata_program.key().as_ref(),
+            mint.key().as_ref(),
+            b"edition",
+        ],
+        seeds::program = metadata_program.key(),
+        bump,
+    )]
+    pub edition: Account<'info, MasterEditionAccount>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    metadata_program: Program<'info, Metadata>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> RentNft<'info> {
+    pub fn rent(&mut self, bumps: &RentNftBumps) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require!(
+            self.rental.renter.is_none(),
+            MarketplaceErrors::AlreadyRented
+        );
+        self.collection_config.validate_trading()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        self.rental.renter = Some(*self.renter.key);
+        self.rental.rented_until = now + self.rental.days as i64 * Rental::ONE_DAY;
+
+        // pay the whole rental upfront to owner and cut protocol fee
+        self.pay_sol()?;
+
+        // move nft to the renter, then delegate it to the rental and freeze it there
+        self.transfer_nft(bumps)?;
+        self.freeze_nft()
+    }
+
+    pub fn pay_sol(&mut self) -> Result<()> {
+        let price = self.rental.total_price();
+        let bips = self.rental.fee as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.renter.to_account_info(),
+                    to: self.owner.to_account_info(),
+                },
+            ),
+            price_minus_fee,
+        )?;
+
+        if fee > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.renter.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                fee,
+            )?;
+        }
+
+        self.rental.emit_rented(self.renter.key(), price, fee)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_nft(&mut self, bumps: &RentNftBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
+        transfer_nft(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferNft {
+                    from: self.vault.to_account_info(),
+                    to: self.renter_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        // vault rent goes back to the owner who paid it
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+
+    pub fn freeze_nft(&mut self) -> Result<()> {
+        let delegate = self.rental.to_account_info();
+        let token_account = self.renter_ata.to_account_info();
+        let token_program = self.token_program.to_account_info();
+
+        approve(
+            CpiContext::new(
+                token_program.clone(),
+                Approve {
+                    to: token_account.clone(),
+                    delegate: delegate.clone(),
+                    authority: self.renter.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        let owner_key = self.owner.key(); // binding to prevent temporary value dropped errors
+        let mint_key = self.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"rental",
+            owner_key.as_ref(),
+            mint_key.as_ref(),
+            &[self.rental.bump],
+        ]];
+
+        FreezeDelegatedAccountCpi::new(
+            &self.metadata_program.to_account_info(),
+            FreezeDelegatedAccountCpiAccounts {
+                delegate: &delegate,
+                token_account: &token_account,
+                edition: &self.edition.to_account_info(),
+                mint: &self.mint.to_account_info(),
+                token_program: &token_program,
+            },
+        )
+        .invoke_signed(signer_seeds)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{CollectionConfig, Global};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCollection<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump,
+        has_one = admin,
+    )]
+    pub global: Account<'info, Global>,
+
+    /// CHECK: collection nft mint, or core collection account, only used as the config seed
+    pub collection_mint: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + CollectionConfig::INIT_SPACE,
+        seeds = [b"collection", collection_mint.key().as_ref()],
+        bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    // cpi programs
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetCollection<'info> {
+    pub fn set(&mut self, enabled: bool, frozen: bool, bumps: &SetCollectionBumps) -> Result<()> {
+        self.collection_config.set_inner(CollectionConfig {
+            collection_mint: (self.collection_mint.key()),
+            enabled: (enabled),
+            fee: (self.collection_config.fee), // only changed through the `SetCollectionFee` timelock
+            frozen: (frozen),
+            bump: (bumps.collection_config),
+        });
+
+        Ok(())
+    }
+}
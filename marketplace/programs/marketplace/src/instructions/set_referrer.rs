@@ -0,0 +1,42 @@
+use crate::{Global, Referrer};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetReferrer<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump,
+        has_one = admin,
+    )]
+    pub global: Account<'info, Global>,
+
+    pub wallet: SystemAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Referrer::INIT_SPACE,
+        seeds = [b"referrer", wallet.key().as_ref()],
+        bump,
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    // cpi programs
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetReferrer<'info> {
+    pub fn set(&mut self, bumps: &SetReferrerBumps) -> Result<()> {
+        // share starts at 0 and is only changed through the `SetReferrerShare` timelock
+        self.referrer.set_inner(Referrer {
+            wallet: (self.wallet.key()),
+            share: (0),
+            bump: (bumps.referrer),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{close_account, transfer, CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    error::MarketplaceErrors, Auction, AuctionClosed, CollectionConfig, Global, Purchased,
+};
+
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    // anyone can settle once the auction is over
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, address = auction.seller)]
+    pub seller: SystemAccount<'info>,
+
+    // highest bidder, or the seller when nobody bid
+    #[account(address = auction.recipient())]
+    pub recipient: SystemAccount<'info>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::authority = recipient,
+        associated_token::mint = mint,
+    )]
+    pub recipient_ata: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::authority = vault,
+        token::mint = mint,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", seller.key().as_ref(), mint.key().as_ref()],
+        bump = auction.bump,
+        close = seller,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(
+        seeds = [b"collection", auction.collection.as_ref()],
+        bump = collection_config.bump,
+    )]
+    pub collection_config: Account<'info, CollectionConfig>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> SettleAuction<'info> {
+    pub fn settle(&mut self, bumps: &SettleAuctionBumps) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= self.auction.end_time,
+            MarketplaceErrors::AuctionNotEnded
+        );
+
+        let sold = self.auction.highest_bid > 0;
+        if sold {
+            // a sale, same rules as purchasing
+            require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+            self.collection_config.validate_trading()?;
+        } else {
+            // nothing sold, same rules as delisting
+            self.global.check_frozen_withdrawal()?;
+
+            emit!(AuctionClosed {
+                seller: self.auction.seller,
+                mint: self.auction.mint,
+                collection: self.auction.collection,
+                refunded_bidder: None,
+                refund: 0,
+                timestamp: now,
+            });
+        }
+
+        // send nft to the recipient and close the vault, auction rent goes back to seller
+        self.withdraw_nft(bumps)?;
+
+        // paid after the token cpis, moving lamports directly before them unbalances the caller
+        if sold {
+            self.pay_sol()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn pay_sol(&mut self) -> Result<()> {
+        let price = self.auction.highest_bid;
+        let bips = self.auction.fee as u64;
+
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+        let price_minus_fee = price - fee;
+
+        // auction account is owned by this program, so lamports can be moved out directly
+        self.auction.sub_lamports(price)?;
+        self.seller.add_lamports(price_minus_fee)?;
+        self.treasury.add_lamports(fee)?;
+
+        emit!(Purchased {
+            buyer: self.auction.highest_bidder,
+            seller: self.seller.key(),
+            mint: self.mint.key(),
+            collection: self.auction.collection,
+            price,
+            fee,
+            royalties: None,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_nft(&mut self, bumps: &SettleAuctionBumps) -> Result<()> {
+        let mint_key = self.mint.key(); // binding to prevent temporary value dropped errors
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bumps.vault]]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault.to_account_info(),
+                    to: self.recipient_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.seller.to_account_info(),
+                authority: self.vault.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    }
+}
@@ -0,0 +1,232 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::{
+    close_account, transfer as transfer_nft, CloseAccount, Token, TokenAccount,
+    Transfer as TransferNft,
+};
+
+use crate::{error::MarketplaceErrors, CollectionConfig, Custody, Global, Offer, Referrer};
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    #[account(mut, address = global.treasury)]
+    pub treasury: SystemAccount<'info>,
+
+    // partner frontend the sweep is made through, paid its share of every fee
+    pub referrer: Option<Account<'info, Referrer>>,
+
+    #[account(mut)]
+    pub referrer_wallet: Option<SystemAccount<'info>>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+}
+
+impl<'info> Sweep<'info> {
+    /// remaining accounts per listing: [seller, mint, buyer_ata, vault, listing, collection_config, listing_referrer]
+    /// listing_referrer is only checked when the listing was made through a referrer, any account otherwise
+    pub const ACCOUNTS_PER_LISTING: usize = 7;
+
+    pub fn sweep(
+        &mut self,
+        max_total: u64,
+        max_prices: &[u64],
+        all_or_nothing: bool,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+        require!(
+            !remaining_accounts.is_empty()
+                && remaining_accounts
+                    .chunks_exact(Self::ACCOUNTS_PER_LISTING)
+                    .remainder()
+                    .is_empty(),
+            MarketplaceErrors::InvalidBatch
+        );
+        require!(
+            max_prices.len() * Self::ACCOUNTS_PER_LISTING == remaining_accounts.len(),
+            MarketplaceErrors::InvalidBatch
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut spent: u64 = 0;
+        let mut filled: usize = 0;
+
+        for (accounts, max_price) in remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_LISTING)
+            .zip(max_prices)
+        {
+            // in best effort mode listings that are gone, expired, untradable, repriced, over budget
+            // or held outside the vault (pnft, delegated, core, compressed) are skipped,
+            // failures past validation still revert the whole sweep
+            match self.validate_listing(accounts, *max_price, spent, max_total, now) {
+                Ok((listing, price)) => {
+                    self.purchase_one(accounts, listing, price)?;
+                    spent += price;
+                    filled += 1;
+                }
+                Err(error) if !all_or_nothing => {
+                    msg!("Skipping listing {}: {:?}", accounts[4].key, error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        require!(filled > 0, MarketplaceErrors::NothingSwept);
+        msg!("Swept {} listings for {} lamports", filled, spent);
+
+        Ok(())
+    }
+
+    pub fn validate_listing(
+        &self,
+        accounts: &'info [AccountInfo<'info>],
+        max_price: u64,
+        spent: u64,
+        max_total: u64,
+        now: i64,
+    ) -> Result<(Account<'info, Offer>, u64)> {
+        let [seller, mint, _, _, listing, collection_config, _] = accounts else {
+            return err!(MarketplaceErrors::InvalidBatch);
+        };
+
+        let (listing_key, _) = Pubkey::find_program_address(
+            &[b"listing", seller.key.as_ref(), mint.key.as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            listing.key(),
+            listing_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        let listing = Account::<Offer>::try_from(listing)?;
+        require!(
+            listing.custody == Custody::Vault,
+            MarketplaceErrors::CustodyNotSupported
+        );
+
+        require!(!listing.is_expired(now), MarketplaceErrors::ListingExpired);
+        listing.validate_buyer(self.buyer.key)?;
+
+        let (config_key, _) =
+            Pubkey::find_program_address(&[b"collection", listing.collection.as_ref()], &crate::ID);
+        require_keys_eq!(
+            collection_config.key(),
+            config_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        Account::<CollectionConfig>::try_from(collection_config)?.validate_trading()?;
+
+        let price = listing.price_for(now, max_price)?;
+        require!(
+            spent.checked_add(price).unwrap() <= max_total,
+            MarketplaceErrors::MaxSpendExceeded
+        );
+
+        Ok((listing, price))
+    }
+
+    pub fn purchase_one(
+        &mut self,
+        accounts: &'info [AccountInfo<'info>],
+        listing: Account<'info, Offer>,
+        price: u64,
+    ) -> Result<()> {
+        let [seller, mint, buyer_ata, vault, _, _, listing_referrer] = accounts else {
+            return err!(MarketplaceErrors::InvalidBatch);
+        };
+
+        let buyer_token = Account::<TokenAccount>::try_from(buyer_ata)?;
+        require_keys_eq!(
+            buyer_token.owner,
+            self.buyer.key(),
+            MarketplaceErrors::InvalidBatchAccount
+        );
+
+        // pay sol price to seller and cut protocol fee
+        let bips = listing.fee as u64;
+        let fee = price.checked_mul(bips).unwrap().checked_div(10000).unwrap(); // price * feebips / 10000
+
+        // referrers are paid out of the protocol fee, treasury keeps the rest
+        let referral = listing.pay_referrers(
+            fee,
+            &self.buyer.to_account_info(),
+            &self.system_program.to_account_info(),
+            Some(listing_referrer),
+            self.referrer.as_deref(),
+            self.referrer_wallet.as_ref().map(|wallet| wallet.as_ref()),
+        )?;
+
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.buyer.to_account_info(),
+                    to: seller.clone(),
+                },
+            ),
+            price - fee,
+        )?;
+
+        if fee - referral > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.buyer.to_account_info(),
+                        to: self.treasury.to_account_info(),
+                    },
+                ),
+                fee - referral,
+            )?;
+        }
+
+        // transfer nft to the buyer, close vault and listing with rent back to seller
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", mint.key.as_ref()], &crate::ID);
+        require_keys_eq!(
+            vault.key(),
+            vault_key,
+            MarketplaceErrors::InvalidBatchAccount
+        );
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint.key.as_ref(), &[vault_bump]]];
+
+        transfer_nft(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferNft {
+                    from: vault.clone(),
+                    to: buyer_ata.clone(),
+                    authority: vault.clone(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.clone(),
+                destination: seller.clone(),
+                authority: vault.clone(),
+            },
+            signer_seeds,
+        ))?;
+
+        listing.emit_purchased(self.buyer.key(), mint.key(), price, fee)?;
+        listing.close(seller.clone())
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::MarketplaceErrors, Global, Offer};
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    pub seller: Signer<'info>,
+
+    /// CHECK: key the listing is seeded with, a mint, core asset or compressed asset id, never read
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"listing", seller.key().as_ref(), asset.key().as_ref()],
+        bump = listing.bump,
+    )]
+    pub listing: Account<'info, Offer>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+}
+
+impl<'info> UpdateListing<'info> {
+    pub fn update(&mut self, price: u64, expires_at: Option<i64>) -> Result<()> {
+        require!(!self.global.frozen, MarketplaceErrors::ProtocolFrozen);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            !self.listing.is_expired(now),
+            MarketplaceErrors::ListingExpired
+        );
+        Offer::validate_expiry(expires_at, self.listing.custody, now)?;
+
+        // dutch auctions keep their schedule, new start price must still be above the floor
+        if let Some(decay) = self.listing.decay {
+            decay.validate(price)?;
+        }
+
+        self.listing.price = price;
+        self.listing.expires_at = expires_at;
+
+        self.listing.emit_updated(self.asset.key())
+    }
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+
+use crate::{error::MarketplaceErrors, events::TradeWithdrawn, Global, Trade};
+
+#[derive(Accounts)]
+pub struct WithdrawTrade<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = taker @ MarketplaceErrors::TakerNotAllowed,
+        seeds = [b"trade", maker.key().as_ref(), trade.id.to_le_bytes().as_ref()],
+        bump = trade.bump,
+    )]
+    pub trade: Account<'info, Trade>,
+
+    #[account(
+        seeds = [b"global"],
+        bump = global.bump
+    )]
+    pub global: Account<'info, Global>,
+
+    // cpi programs
+    token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawTrade<'info> {
+    /// remaining accounts per requested nft, in requested order: [vault, taker_ata]
+    pub const ACCOUNTS_PER_NFT: usize = 2;
+
+    pub fn withdraw(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        // Only withdrawing is allowed when the protocol is in frozen state but with 1 week delay
+        self.global.check_frozen_withdrawal()?;
+        require!(
+            self.trade.taker_deposited,
+            MarketplaceErrors::TradeNotDeposited
+        );
+
+        let requested = self.trade.requested.clone();
+        require!(
+            remaining_accounts.len() == requested.len() * Self::ACCOUNTS_PER_NFT,
+            MarketplaceErrors::InvalidTradeAccounts
+        );
+
+        // nfts and vault rent back to the taker, the trade stays open for a new deposit
+        for (accounts, mint) in remaining_accounts
+            .chunks(Self::ACCOUNTS_PER_NFT)
+            .zip(requested)
+        {
+            let [vault, taker_ata] = accounts else {
+                return err!(MarketplaceErrors::InvalidTradeAccounts);
+            };
+            Trade::release_nft(
+                mint,
+                vault,
+                taker_ata,
+                self.taker.key(),
+                &self.taker.to_account_info(),
+                &self.token_program.to_account_info(),
+            )?;
+        }
+
+        self.trade.taker_deposited = false;
+
+        emit!(TradeWithdrawn {
+            maker: self.trade.maker,
+            taker: self.trade.taker,
+            id: self.trade.id,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
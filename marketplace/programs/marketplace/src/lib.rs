@@ -1,11 +1,25 @@
+// anchor 0.31's injected idl instructions call the deprecated `AccountInfo::realloc` from a `__private`
+// module that `#[program]` emits at the crate root, so the allow can't sit any lower than here
+// every module of ours turns the lint back on, it only stays off for that generated code
+#![allow(deprecated)]
+
+#[warn(deprecated)]
 pub mod constants;
+#[warn(deprecated)]
 pub mod error;
+#[warn(deprecated)]
+pub mod events;
+#[warn(deprecated)]
 pub mod instructions;
+#[warn(deprecated)]
 pub mod state;
+#[warn(deprecated)]
+pub mod utils;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -15,14 +29,25 @@ declare_id!("6G5KL3C7vYmWdCvkXahjCJmx57VrGDQrync2oR7PgNaw");
 /// while enforcing protocol-level admin controls such as fee updates and emergency freezing.
 ///
 /// Admin actions include:
-/// - Setting and updating protocol fee (subject to time-based restrictions)
-/// - Freezing and unfreezing the protocol
+/// - Setting the protocol fee, treasury and admin through timelocked proposals
+/// - Freezing and unfreezing the protocol, immediately for emergencies
+/// - Curating tradable collections with per-collection fee and freeze
+/// - Registering partner frontends sharing the protocol fee
 ///
 /// User actions include:
-/// - Listing NFTs for sale
+/// - Listing NFTs and programmable NFTs for sale, at a fixed price or as a dutch auction
+/// - Listing Metaplex Core assets and compressed NFTs (Bubblegum)
+/// - Reserving a listing for a specific buyer (private OTC listings)
+/// - Listing NFTs without custody, delegated and frozen in the seller's wallet
 /// - Purchasing listed NFTs
+/// - Batch listing, batch delisting and sweeping several listings at once
 /// - Delisting NFTs (even under protocol freeze, with enforced delay)
+/// - Bidding on a specific NFT or on any NFT of a verified collection
+/// - Auctioning NFTs in timed english auctions
+/// - Trading NFTs for NFTs with another wallet, optionally adding SOL, both sides escrowed until the swap
+/// - Renting NFTs out for a fixed number of days, reclaimable by anyone once the rental is over
 ///
+#[warn(deprecated)]
 #[program]
 pub mod marketplace {
     use super::*;
@@ -56,18 +81,74 @@ pub mod marketplace {
         ctx.accounts.set(freeze)
     }
 
-    /// Updates the protocol fee, with strict time-based rules:
+    /// Queues an admin action behind a timelock: protocol or collection fee update, treasury change,
+    /// admin transfer or referrer share update.
     ///
-    /// @param ctx Accounts context including admin and global config.
-    /// @param new_fee New fee value in basis points upto 0.5% limit
+    /// @param ctx Accounts context including admin, global config and queued action PDA.
+    /// @param action Admin action to execute once its delay has passed.
+    ///
+    /// @notice Protocol and collection fee updates wait **2 weeks**, everything else waits 1 week.
+    /// Collection fees are bound by the same 0.5% limit, referrer shares by half of the fee.
+    /// Admin can only propose a fee update if:
+    /// - No other fee update is queued, `get_fees` reads the pending fee from it.
+    /// - The new fee is upto 0.5% limit.
+    ///
+    /// @dev Prevents spammy or malicious changes. Encourages predictability.
+    /// `admin` can itself be a multisig vault, every admin instruction only needs its signature.
+    pub fn propose_action(ctx: Context<ProposeAction>, action: AdminAction) -> Result<()> {
+        ctx.accounts.propose(action, &ctx.bumps)
+    }
+
+    /// Executes a queued admin action once its timelock elapsed, callable by anyone.
+    ///
+    /// @param ctx Accounts context including global config, queued action and its proposer.
+    /// Collection fee and referrer share updates also need the collection config or referrer account.
+    ///
+    /// @notice Existing listings keep the fee snapshotted when they were created.
+    /// @dev Closes the queued action with rent back to its proposer.
+    pub fn execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+        ctx.accounts.execute()
+    }
+
+    /// Cancels a queued admin action before it's executed.
+    ///
+    /// @param ctx Accounts context including admin, global config, queued action and its proposer.
+    ///
+    /// @dev Closes the queued action with rent back to its proposer.
+    pub fn cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    /// Returns the current and pending protocol fees with their effective timestamps.
+    ///
+    /// @param ctx Accounts context including global config, and the queued fee update while one is pending.
+    ///
+    /// @dev Read-only, meant to be simulated by clients to show sellers the fee they'll pay.
+    pub fn get_fees(ctx: Context<GetFees>) -> Result<FeeSchedule> {
+        ctx.accounts.get()
+    }
+
+    /// Creates or updates the config of a collection allowed to trade on the marketplace.
+    ///
+    /// @param ctx Accounts context including admin, global config and collection config.
+    /// @param enabled Whether NFTs of this verified collection can be listed and purchased.
+    /// @param frozen Freezes listing and purchasing for this collection only.
+    ///
+    /// @notice Listing requires the NFT to be a verified member of an enabled collection.
+    /// Core collections are configured by their collection account instead of a mint.
+    /// @dev Fee overrides are only changed through the timelocked `SetCollectionFee` action.
+    pub fn set_collection(ctx: Context<SetCollection>, enabled: bool, frozen: bool) -> Result<()> {
+        ctx.accounts.set(enabled, frozen, &ctx.bumps)
+    }
+
+    /// Registers a partner frontend entitled to a share of the protocol fee.
     ///
-    /// @notice Admin can only update the fee if:
-    /// - At least 1 week has passed since the last update.
-    /// - The new fee takes effect **2 weeks after** this transaction for sellers.
+    /// @param ctx Accounts context including admin, global config, partner wallet and referrer account.
     ///
-    /// @dev Prevents spammy or malicious fee changes. Encourages predictability.
-    pub fn update_fee(ctx: Context<UpdateFee>, new_fee: u16) -> Result<()> {
-        ctx.accounts.update(new_fee)
+    /// @notice Share starts at 0 and is set through the timelocked `SetReferrerShare` action.
+    /// Setting it back to 0 stops new referral payouts, existing listings keep their snapshot.
+    pub fn set_referrer(ctx: Context<SetReferrer>) -> Result<()> {
+        ctx.accounts.set(&ctx.bumps)
     }
 
     //=====================
@@ -80,11 +161,55 @@ pub mod marketplace {
     ///
     /// @param ctx Accounts context including seller, NFT metadata, and offer PDA.
     /// @param price Listing price in native sol lamports.
+    /// @param expires_at Optional unix timestamp after which the NFT can't be purchased.
+    /// @param allowed_buyer Optional wallet the listing is reserved for, e.g. for OTC deals.
     ///
     /// @notice Listing is disallowed while protocol is frozen.
+    /// Listing through a registered referrer snapshots its fee share into the listing.
     /// @dev Creates a new `Offer` PDA and stores price + metadata.
-    pub fn list_nft(ctx: Context<List>, price: u64) -> Result<()> {
-        ctx.accounts.list(price, &ctx.bumps)
+    pub fn list_nft(
+        ctx: Context<List>,
+        price: u64,
+        expires_at: Option<i64>,
+        allowed_buyer: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts
+            .list(price, None, expires_at, allowed_buyer, &ctx.bumps)
+    }
+
+    /// List an NFT as a dutch auction whose price decays over time.
+    ///
+    /// @param ctx Accounts context including seller, NFT metadata, and offer PDA.
+    /// @param start_price Price in native sol lamports at `decay.start_time`.
+    /// @param decay Linear or exponential decay schedule down to `decay.floor_price`.
+    /// @param expires_at Optional unix timestamp after which the NFT can't be purchased.
+    ///
+    /// @notice Listing is disallowed while protocol is frozen.
+    /// @dev Price is computed from `Clock` when the NFT is purchased.
+    pub fn list_nft_dutch(
+        ctx: Context<List>,
+        start_price: u64,
+        decay: DutchAuction,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .list(start_price, Some(decay), expires_at, None, &ctx.bumps)
+    }
+
+    /// Update the price and expiry of a listed NFT in place, without delisting.
+    ///
+    /// @param ctx Accounts context including seller, the listed mint, core asset or compressed asset id and offer.
+    /// @param price New price in native sol lamports, start price for dutch auctions.
+    /// @param expires_at New expiry unix timestamp, None to never expire. Vault listings only.
+    ///
+    /// @notice Updating is disallowed while protocol is frozen or once the listing expired.
+    /// @dev Works for every custody, the NFT stays where it is and the offer PDA is reused.
+    pub fn update_listing(
+        ctx: Context<UpdateListing>,
+        price: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.update(price, expires_at)
     }
 
     /// Delist an NFT that was previously listed.
@@ -97,13 +222,471 @@ pub mod marketplace {
         ctx.accounts.delist(&ctx.bumps)
     }
 
+    /// List several NFTs of one collection in a single transaction.
+    ///
+    /// @param ctx Accounts context including seller and collection config, with
+    /// `[mint, seller_ata, vault, listing, metadata]` per NFT as remaining accounts.
+    /// @param prices Listing price in native sol lamports for each NFT, in order.
+    /// @param expires_at Optional unix timestamp after which the NFTs can't be purchased.
+    ///
+    /// @notice Listing is disallowed while protocol is frozen, any failing NFT reverts the batch.
+    pub fn batch_list<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchList<'info>>,
+        prices: Vec<u64>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts
+            .batch_list(&prices, expires_at, ctx.remaining_accounts)
+    }
+
+    /// Delist several NFTs in a single transaction.
+    ///
+    /// @param ctx Accounts context including seller, with `[mint, seller_ata, vault, listing]`
+    /// per NFT as remaining accounts.
+    ///
+    /// @notice Delisting is delayed by 1 week, if protocol is currently frozen.
+    pub fn batch_delist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchDelist<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.batch_delist(ctx.remaining_accounts)
+    }
+
+    /// Purchase several listings in a single transaction.
+    ///
+    /// @param ctx Accounts context including buyer, treasury and optional referrer, with
+    /// `[seller, mint, buyer_ata, vault, listing, collection_config, listing_referrer]` per listing as remaining accounts.
+    /// @param max_total Maximum lamports spent on listing prices across the sweep.
+    /// @param max_prices Most lamports the buyer agrees to pay for each listing, in remaining accounts order.
+    /// @param all_or_nothing Revert on any unpurchasable listing (true) or skip it (false).
+    ///
+    /// @notice Purchasing is disallowed while protocol is frozen.
+    /// @dev Best effort sweeps skip listings that are gone, expired, untradable, above their max price, over budget
+    /// or not held in a vault. Referrers get the same share of every fee as in `purchase_nft`.
+    pub fn sweep<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Sweep<'info>>,
+        max_total: u64,
+        max_prices: Vec<u64>,
+        all_or_nothing: bool,
+    ) -> Result<()> {
+        ctx.accounts.sweep(
+            max_total,
+            &max_prices,
+            all_or_nothing,
+            ctx.remaining_accounts,
+        )
+    }
+
+    /// List an NFT without moving it out of the seller's wallet.
+    ///
+    /// @param ctx Accounts context including seller, NFT metadata, edition and offer PDA.
+    /// @param price Listing price in native sol lamports, start price for dutch auctions.
+    /// @param decay Optional decay schedule, None for fixed price listings.
+    ///
+    /// @notice Listing is disallowed while protocol is frozen.
+    /// Listing through a registered referrer snapshots its fee share into the listing.
+    /// @dev Offer PDA becomes delegate of the seller's token account and freezes it.
+    pub fn list_nft_delegated(
+        ctx: Context<ListDelegated>,
+        price: u64,
+        decay: Option<DutchAuction>,
+    ) -> Result<()> {
+        ctx.accounts.list(price, decay, &ctx.bumps)
+    }
+
+    /// Delist a non-custodial listing, thawing the NFT in the seller's wallet.
+    ///
+    /// @param ctx Accounts context including seller, edition and offer.
+    ///
+    /// @notice Delisting is delayed by 1 week, if protocol is currently frozen.
+    pub fn delist_nft_delegated(ctx: Context<DelistDelegated>) -> Result<()> {
+        ctx.accounts.delist()
+    }
+
+    /// Purchase a non-custodial listing straight from the seller's wallet.
+    ///
+    /// @param ctx Accounts context including buyer, seller, edition and offer.
+    /// @param max_price Most lamports the buyer agrees to pay, guards against the seller raising the price.
+    ///
+    /// @notice Purchasing is disallowed while protocol is frozen or once the listing expired.
+    /// Referrers of the listing and of the purchase are paid their share out of the protocol fee.
+    /// @dev Offer PDA thaws the NFT and transfers it as delegate.
+    pub fn purchase_nft_delegated(ctx: Context<PurchaseDelegated>, max_price: u64) -> Result<()> {
+        ctx.accounts.purchase(max_price)
+    }
+
+    /// List a programmable NFT (pNFT) for sale, at a fixed price or as a dutch auction.
+    ///
+    /// @param ctx Accounts context including seller, token records, rule set and offer PDA.
+    /// @param price Listing price in native sol lamports, start price for dutch auctions.
+    /// @param decay Optional decay schedule, None for fixed price listings.
+    ///
+    /// @notice Listing is disallowed while protocol is frozen.
+    /// Listing through a registered referrer snapshots its fee share into the listing.
+    /// @dev Moves the pNFT with Token Metadata `Transfer` into an ATA owned by the offer PDA.
+    pub fn list_pnft(
+        ctx: Context<ListPnft>,
+        price: u64,
+        decay: Option<DutchAuction>,
+    ) -> Result<()> {
+        ctx.accounts.list(price, decay, &ctx.bumps)
+    }
+
+    /// Delist a programmable NFT (pNFT) that was previously listed.
+    ///
+    /// @param ctx Accounts context including seller, token records, rule set and offer.
+    ///
+    /// @notice Delisting is delayed by 1 week, if protocol is currently frozen.
+    /// @dev Closes the offer PDA's ATA and the offer with rent back to the seller.
+    pub fn delist_pnft(ctx: Context<DelistPnft>) -> Result<()> {
+        ctx.accounts.delist()
+    }
+
+    /// Purchase a listed programmable NFT (pNFT) from the marketplace.
+    ///
+    /// @param ctx Accounts context including buyer, seller, token records, rule set and offer.
+    /// @param max_price Most lamports the buyer agrees to pay, guards against the seller raising the price.
+    ///
+    /// @notice Purchasing is disallowed while protocol is frozen or once the listing expired.
+    /// Referrers of the listing and of the purchase are paid their share out of the protocol fee.
+    /// @dev Closes the offer PDA's ATA and the offer with rent back to the seller.
+    pub fn purchase_pnft(ctx: Context<PurchasePnft>, max_price: u64) -> Result<()> {
+        ctx.accounts.purchase(max_price)
+    }
+
+    /// List a Metaplex Core asset for sale, at a fixed price or as a dutch auction.
+    ///
+    /// @param ctx Accounts context including seller, core asset, its collection, collection config and offer PDA.
+    /// @param price Listing price in native sol lamports, start price for dutch auctions.
+    /// @param decay Optional decay schedule, None for fixed price listings.
+    ///
+    /// @notice Listing is disallowed while protocol or the asset's core collection is frozen.
+    /// Listing through a registered referrer snapshots its fee share into the listing.
+    /// @dev Transfers the asset to the offer PDA with Core `TransferV1`.
+    pub fn list_core(
+        ctx: Context<ListCore>,
+        price: u64,
+        decay: Option<DutchAuction>,
+    ) -> Result<()> {
+        ctx.accounts.list(price, decay, &ctx.bumps)
+    }
+
+    /// Delist a Metaplex Core asset that was previously listed.
+    ///
+    /// @param ctx Accounts context including seller, core asset and offer.
+    ///
+    /// @notice Delisting is delayed by 1 week, if protocol is currently frozen.
+    pub fn delist_core(ctx: Context<DelistCore>) -> Result<()> {
+        ctx.accounts.delist()
+    }
+
+    /// Purchase a listed Metaplex Core asset from the marketplace.
+    ///
+    /// @param ctx Accounts context including buyer, seller, core asset, collection config and offer.
+    /// @param max_price Most lamports the buyer agrees to pay, guards against the seller raising the price.
+    ///
+    /// @notice Purchasing is disallowed while protocol is frozen or once the listing expired.
+    /// Referrers of the listing and of the purchase are paid their share out of the protocol fee.
+    pub fn purchase_core(ctx: Context<PurchaseCore>, max_price: u64) -> Result<()> {
+        ctx.accounts.purchase(max_price)
+    }
+
+    /// List a compressed NFT (Bubblegum) for sale, at a fixed price or as a dutch auction.
+    ///
+    /// @param ctx Accounts context including seller, tree config, merkle tree, collection config and offer PDA,
+    /// with the merkle proof nodes as remaining accounts.
+    /// @param asset_id Asset id of the compressed NFT, seeds the offer PDA.
+    /// @param leaf Current root and hashes of the leaf, as returned by the DAS api.
+    /// @param metadata Borsh encoded Bubblegum `MetadataArgs` of the leaf, must hash to its data hash.
+    /// @param price Listing price in native sol lamports, start price for dutch auctions.
+    /// @param decay Optional decay schedule, None for fixed price listings.
+    ///
+    /// @notice Listing is disallowed while protocol or the leaf's verified collection is frozen.
+    /// Listing through a registered referrer snapshots its fee share into the listing.
+    /// @dev Transfers leaf ownership to the offer PDA with Bubblegum `Transfer`.
+    pub fn list_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListCompressed<'info>>,
+        asset_id: Pubkey,
+        leaf: CompressedLeaf,
+        metadata: Vec<u8>,
+        price: u64,
+        decay: Option<DutchAuction>,
+    ) -> Result<()> {
+        ctx.accounts.list(
+            asset_id,
+            leaf,
+            &metadata,
+            price,
+            decay,
+            ctx.remaining_accounts,
+            &ctx.bumps,
+        )
+    }
+
+    /// Delist a compressed NFT (Bubblegum) that was previously listed.
+    ///
+    /// @param ctx Accounts context including seller, tree config, merkle tree and offer,
+    /// with the merkle proof nodes as remaining accounts.
+    /// @param asset_id Asset id of the compressed NFT.
+    /// @param leaf Current root and hashes of the leaf, as returned by the DAS api.
+    ///
+    /// @notice Delisting is delayed by 1 week, if protocol is currently frozen.
+    pub fn delist_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DelistCompressed<'info>>,
+        asset_id: Pubkey,
+        leaf: CompressedLeaf,
+    ) -> Result<()> {
+        ctx.accounts.delist(asset_id, leaf, ctx.remaining_accounts)
+    }
+
+    /// Purchase a listed compressed NFT (Bubblegum) from the marketplace.
+    ///
+    /// @param ctx Accounts context including buyer, seller, tree config, merkle tree, collection config and offer,
+    /// with the merkle proof nodes as remaining accounts.
+    /// @param asset_id Asset id of the compressed NFT.
+    /// @param leaf Current root and hashes of the leaf, as returned by the DAS api.
+    /// @param max_price Most lamports the buyer agrees to pay, guards against the seller raising the price.
+    ///
+    /// @notice Purchasing is disallowed while protocol is frozen or once the listing expired.
+    /// Referrers of the listing and of the purchase are paid their share out of the protocol fee.
+    pub fn purchase_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseCompressed<'info>>,
+        asset_id: Pubkey,
+        leaf: CompressedLeaf,
+        max_price: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .purchase(asset_id, leaf, max_price, ctx.remaining_accounts)
+    }
+
+    /// List an NFT for rent over a fixed number of days.
+    ///
+    /// @param ctx Accounts context including owner, NFT metadata, vault and rental PDA.
+    /// @param price_per_day Rental price per day in native sol lamports.
+    /// @param days Rental duration in days.
+    ///
+    /// @notice Listing is disallowed while protocol is frozen.
+    /// @dev NFT is held in the same `vault` PDA used by fixed price listings until it is rented.
+    pub fn list_rental(ctx: Context<ListRental>, price_per_day: u64, days: u16) -> Result<()> {
+        ctx.accounts.list(price_per_day, days, &ctx.bumps)
+    }
+
+    /// Delist a rental that isn't rented out, returning the NFT to its owner.
+    ///
+    /// @param ctx Accounts context including owner, vault and rental.
+    ///
+    /// @notice Delisting is delayed by 1 week, if protocol is currently frozen.
+    pub fn delist_rental(ctx: Context<DelistRental>) -> Result<()> {
+        ctx.accounts.delist(&ctx.bumps)
+    }
+
+    /// Rent a listed NFT for the rental duration.
+    ///
+    /// @param ctx Accounts context including renter, owner, vault, rental and edition.
+    ///
+    /// @notice Renting is disallowed while protocol is frozen.
+    /// @dev Renter pays `price_per_day * days` upfront, protocol fee goes to treasury.
+    /// The NFT moves to the renter's wallet, delegated to the rental PDA and frozen there.
+    pub fn rent_nft(ctx: Context<RentNft>) -> Result<()> {
+        ctx.accounts.rent(&ctx.bumps)
+    }
+
+    /// Return a rented NFT to its owner once the rental is over, callable by anyone.
+    ///
+    /// @param ctx Accounts context including owner, renter token account, rental and edition.
+    ///
+    /// @notice Follows delisting rules, delayed by 1 week if protocol is currently frozen.
+    /// @dev Rental PDA thaws the NFT and transfers it as delegate, rental rent goes back to the owner.
+    pub fn reclaim(ctx: Context<Reclaim>) -> Result<()> {
+        ctx.accounts.reclaim()
+    }
+
+    /// Propose trading some of the maker's NFTs, plus optional SOL, for NFTs of another wallet.
+    ///
+    /// @param ctx Accounts context including maker, taker and trade PDA.
+    /// Remaining accounts per offered NFT: [mint, maker_ata, vault, metadata, collection_config].
+    /// @param id Maker picked id of the trade, part of the trade PDA seeds.
+    /// @param requested Mints of the taker's NFTs asked in return.
+    /// @param lamports SOL in native lamports added by the maker, escrowed in the trade PDA.
+    ///
+    /// @notice Proposing is disallowed while protocol or an offered NFT's collection is frozen,
+    /// offered NFTs have to be verified members of enabled collections.
+    /// @dev Offered NFTs are held in the same `vault` PDAs used by fixed price listings.
+    pub fn propose_trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProposeTrade<'info>>,
+        id: u64,
+        requested: Vec<Pubkey>,
+        lamports: u64,
+    ) -> Result<()> {
+        ctx.accounts
+            .propose(id, requested, lamports, ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    /// Cancel a trade, returning the escrowed NFTs and SOL to the maker and any deposit to the taker.
+    ///
+    /// @param ctx Accounts context including maker, taker and trade.
+    /// Remaining accounts per offered NFT: [vault, maker_ata], then, if the taker deposited,
+    /// per requested NFT: [vault, taker_ata].
+    ///
+    /// @notice Cancelling is delayed by 1 week, if protocol is currently frozen.
+    pub fn cancel_trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelTrade<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.cancel(ctx.remaining_accounts)
+    }
+
+    /// Escrow the requested NFTs of a trade, required before the taker can accept it.
+    ///
+    /// @param ctx Accounts context including taker, maker and trade.
+    /// Remaining accounts per requested NFT: [mint, taker_ata, vault, metadata, collection_config].
+    ///
+    /// @notice Depositing is disallowed while protocol or a requested NFT's collection is frozen,
+    /// only the designated taker can deposit.
+    /// @dev Requested NFTs are held in the same `vault` PDAs as offered NFTs and listings.
+    pub fn deposit_trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositTrade<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.deposit(ctx.remaining_accounts)
+    }
+
+    /// Withdraw the taker's deposit from a trade, which stays open.
+    ///
+    /// @param ctx Accounts context including taker, maker and trade.
+    /// Remaining accounts per requested NFT: [vault, taker_ata].
+    ///
+    /// @notice Withdrawing is delayed by 1 week, if protocol is currently frozen.
+    pub fn withdraw_trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawTrade<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.withdraw(ctx.remaining_accounts)
+    }
+
+    /// Accept a trade, swapping both escrowed sides atomically.
+    ///
+    /// @param ctx Accounts context including taker, maker and trade.
+    /// Remaining accounts: the collection config of each of the trade's collections, then
+    /// per offered NFT: [vault, taker_ata], then per requested NFT: [vault, maker_ata].
+    ///
+    /// @notice Accepting is disallowed while protocol or any traded collection is frozen,
+    /// only the designated taker can accept
+    /// and only after depositing the requested NFTs.
+    /// @dev No protocol fee is charged on trades, escrowed SOL goes to the taker in full.
+    pub fn accept_trade<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptTrade<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.accept(ctx.remaining_accounts)
+    }
+
+    /// Return an expired listing's NFT to its seller, callable by anyone.
+    ///
+    /// @param ctx Accounts context including seller, vault and offer.
+    ///
+    /// @notice Follows delisting rules, delayed by 1 week if protocol is currently frozen.
+    /// @dev Closes vault and offer with rent back to the seller.
+    pub fn expire_listing(ctx: Context<ExpireListing>) -> Result<()> {
+        ctx.accounts.expire(&ctx.bumps)
+    }
+
     /// Purchase a listed NFT from the marketplace.
     ///
     /// @param ctx Accounts context including buyer, seller, offer, and vaults.
+    /// @param max_price Most lamports the buyer agrees to pay, guards against the seller raising the price.
     ///
-    /// @notice Purchasing is disallowed while protocol is frozen.
+    /// @notice Purchasing is disallowed while protocol is frozen or once the listing expired.
+    /// Private listings can only be purchased by their `allowed_buyer`.
+    /// Referrers of the listing and of the purchase are paid their share out of the protocol fee.
     /// @dev Handles SOL transfer, fee distribution, and NFT ownership change.
-    pub fn purchase_nft(ctx: Context<Purchase>) -> Result<()> {
-        ctx.accounts.purchase(&ctx.bumps)
+    pub fn purchase_nft(ctx: Context<Purchase>, max_price: u64) -> Result<()> {
+        ctx.accounts.purchase(max_price, &ctx.bumps)
+    }
+
+    /// Place a bid on a specific NFT, or a collection-wide offer on any NFT of a verified collection.
+    ///
+    /// @param ctx Accounts context including bidder, target mint and bid PDA.
+    /// @param price Bid price in native sol lamports, escrowed in the bid PDA.
+    /// @param collection Whether the target mint is a collection mint (true) or a single NFT (false).
+    ///
+    /// @notice Bidding is disallowed while protocol is frozen.
+    pub fn place_bid(ctx: Context<PlaceBid>, price: u64, collection: bool) -> Result<()> {
+        ctx.accounts.place(price, collection, &ctx.bumps)
+    }
+
+    /// Cancel a bid and get the escrowed lamports back.
+    ///
+    /// @param ctx Accounts context including bidder and bid.
+    ///
+    /// @notice Cancelling is delayed by 1 week, if protocol is currently frozen.
+    pub fn cancel_bid(ctx: Context<CancelBid>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    /// Accept a bid by delivering the NFT to the bidder.
+    ///
+    /// @param ctx Accounts context including seller, bidder, bid, NFT metadata and collection config.
+    ///
+    /// @notice Accepting is disallowed while protocol or the NFT's collection is frozen.
+    /// @dev Seller receives the escrowed lamports minus the collection fee, bid rent goes back to bidder.
+    pub fn accept_bid(ctx: Context<AcceptBid>) -> Result<()> {
+        ctx.accounts.accept()
+    }
+
+    /// Put an NFT up for a timed english auction.
+    ///
+    /// @param ctx Accounts context including seller, NFT metadata, collection config, vault and auction PDA.
+    /// @param reserve_price Minimum first bid in native sol lamports.
+    /// @param min_increment Minimum raise in lamports over the current highest bid.
+    /// @param start_time Unix timestamp from which bids are accepted.
+    /// @param end_time Unix timestamp after which the auction can be settled.
+    ///
+    /// @notice Auctions can't be created while protocol or the NFT's collection is frozen,
+    /// the NFT has to be a verified member of an enabled collection.
+    /// @dev NFT is held in the same `vault` PDA used by fixed price listings, collection fee is snapshotted.
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        reserve_price: u64,
+        min_increment: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        ctx.accounts.create(
+            reserve_price,
+            min_increment,
+            start_time,
+            end_time,
+            &ctx.bumps,
+        )
+    }
+
+    /// Bid on a running auction.
+    ///
+    /// @param ctx Accounts context including bidder, previous highest bidder and auction.
+    /// @param amount Bid in native sol lamports, escrowed in the auction PDA.
+    ///
+    /// @notice Bidding is disallowed while protocol is frozen, and for the auction's seller.
+    /// @dev Previous highest bidder is refunded in the same transaction.
+    pub fn place_auction_bid(ctx: Context<AuctionBid>, amount: u64) -> Result<()> {
+        ctx.accounts.bid(amount)
+    }
+
+    /// Settle an auction after its end time, callable by anyone.
+    ///
+    /// @param ctx Accounts context including seller, recipient, vault, auction and collection config.
+    ///
+    /// @notice Highest bidder gets the NFT and seller gets the bid minus the collection fee.
+    /// Without bids the NFT goes back to the seller.
+    /// @dev Follows purchase freeze rules with bids, and delist freeze rules without.
+    /// Auctions with a bid stuck behind a frozen protocol or collection are unwound with `refund_auction`.
+    pub fn settle_auction(ctx: Context<SettleAuction>) -> Result<()> {
+        ctx.accounts.settle(&ctx.bumps)
+    }
+
+    /// Unwind an auction while the protocol or its collection is halted, callable by anyone.
+    ///
+    /// @param ctx Accounts context including seller, highest bidder, vault, auction and collection config.
+    ///
+    /// @notice Only allowed 1 week after the protocol got frozen, whether or not the auction ended.
+    /// A disabled or frozen collection allows it right away.
+    /// @dev Highest bidder gets the escrowed bid back and the NFT goes back to the seller.
+    pub fn refund_auction(ctx: Context<RefundAuction>) -> Result<()> {
+        ctx.accounts.refund(&ctx.bumps)
     }
 }
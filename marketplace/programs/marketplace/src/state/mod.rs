@@ -1,16 +1,38 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    metadata::{mpl_token_metadata, MetadataAccount},
+    token::{
+        close_account, initialize_account3, transfer as transfer_nft, CloseAccount,
+        InitializeAccount3, Mint, TokenAccount, Transfer as TransferNft,
+    },
+};
+use mpl_bubblegum::{hash::hash_metadata, types::MetadataArgs};
+
+use crate::{
+    constants::MAX_TRADE_NFTS,
+    error::MarketplaceErrors,
+    events::{
+        Delisted, Listed, ListingUpdated, Purchased, Reclaimed, RentalDelisted, RentalListed,
+        Rented,
+    },
+    utils::create_pda_account,
+};
 
 #[account]
 #[derive(InitSpace)]
 
 pub struct Global {
     pub admin: Pubkey,
-    pub treasury: Pubkey, //sol address where protocol fee is stored
-    pub fee: u16,         // Current protocol fee on purchases
-    pub new_fee: u16,     // Updated fee by admin , enforced after 14 day delay
-    pub new_fee_at: i64,  // time when fee updated, after two weeks from which new_fee is applicable
+    pub treasury: Pubkey,        //sol address where protocol fee is stored
+    pub fee: u16,                // Current protocol fee, snapshotted into listings
+    pub fee_effective_at: i64,   // time from which `fee` has been applied
+    pub fee_action: Option<u64>, // id of the queued `UpdateFee` action, holding the pending fee
     pub frozen: bool,
     pub frozen_at: i64,
+    pub action_count: u64, // id of the next queued admin action
     pub bump: u8,
 }
 
@@ -18,8 +40,726 @@ pub struct Global {
 #[derive(InitSpace)]
 pub struct Offer {
     pub seller: Pubkey,
-    pub price: u64,
+    pub price: u64,                  // fixed price, or start price of a dutch auction
+    pub decay: Option<DutchAuction>, // price decay schedule, None for fixed price listings
+    pub expires_at: Option<i64>,     // purchases rejected from here on, None never expires
+    pub collection: Pubkey,          // verified collection of the listed nft
+    pub allowed_buyer: Option<Pubkey>, // only wallet allowed to purchase, None for public listings
+    pub fee: u16,                    // fee in bips at listing time, charged on purchase
+    pub referrer: Option<Pubkey>,    // wallet of the frontend the nft was listed through
+    pub referrer_share: u16,         // share of the fee in bips owed to `referrer`
+    pub custody: Custody,            // where the listed nft is held until sold or delisted
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Rental {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,     // verified collection of the rented nft
+    pub price_per_day: u64,     // lamports paid by the renter for each day
+    pub days: u16,              // fixed rental duration
+    pub fee: u16,               // fee in bips at listing time, charged on rent
+    pub renter: Option<Pubkey>, // wallet holding the nft, None while waiting in the vault
+    pub rented_until: i64,      // nft can be reclaimed from the renter from here on
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Trade {
+    pub maker: Pubkey,
+    pub taker: Pubkey, // only wallet allowed to accept the trade
+    pub id: u64,       // picked by the maker, tells apart trades of the same maker
+    #[max_len(MAX_TRADE_NFTS)]
+    pub offered: Vec<Pubkey>, // mints of the maker's nfts, escrowed in their vaults
+    #[max_len(MAX_TRADE_NFTS)]
+    pub requested: Vec<Pubkey>, // mints of the taker's nfts, escrowed in their vaults once deposited
+    pub lamports: u64, // sol added by the maker, escrowed in this account on top of its rent
+    pub taker_deposited: bool, // taker escrowed the requested nfts, the trade can be accepted
+    #[max_len(2 * MAX_TRADE_NFTS)]
+    pub collections: Vec<Pubkey>, // configured collections of the escrowed nfts, checked again on accept
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionConfig {
+    pub collection_mint: Pubkey,
+    pub enabled: bool,    // only enabled collections can be listed and purchased
+    pub fee: Option<u16>, // overrides protocol fee for this collection
+    pub frozen: bool,     // freezes listing and purchasing of this collection only
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Custody {
+    Vault,        // `[b"vault", mint]` ata, the only custody `expire_listing` can reclaim from
+    Programmable, // listing owned ata, moved with token metadata transfers
+    Delegated,    // frozen in the seller's ata with the listing as delegate
+    Core,         // core asset owned by the listing
+    Compressed,   // bubblegum leaf owned by the listing, transferred to it on list
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PriceCurve {
+    Linear,
+    Exponential { half_life: i64 }, // excess over floor halves every `half_life` seconds
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct DutchAuction {
+    pub curve: PriceCurve,
+    pub floor_price: u64,
+    pub start_time: i64,
+    pub end_time: i64, // price sits at floor from here on
+}
+
+impl DutchAuction {
+    pub fn validate(&self, start_price: u64) -> Result<()> {
+        require!(
+            self.floor_price <= start_price && self.start_time < self.end_time,
+            MarketplaceErrors::InvalidPriceDecay
+        );
+        if let PriceCurve::Exponential { half_life } = self.curve {
+            require!(half_life > 0, MarketplaceErrors::InvalidPriceDecay);
+        }
+        Ok(())
+    }
+
+    /// price at `now`, declining from `start_price` to `floor_price` between start and end time
+    pub fn price_at(&self, start_price: u64, now: i64) -> u64 {
+        if now <= self.start_time {
+            return start_price;
+        }
+        if now >= self.end_time {
+            return self.floor_price;
+        }
+
+        let excess = (start_price - self.floor_price) as u128;
+        let elapsed = (now - self.start_time) as u128;
+
+        let remaining = match self.curve {
+            PriceCurve::Linear => {
+                let duration = (self.end_time - self.start_time) as u128;
+                excess * (duration - elapsed) / duration
+            }
+            PriceCurve::Exponential { half_life } => {
+                // halve once per full half life, then interpolate linearly inside the current one
+                let half_life = half_life as u128;
+                let halvings = elapsed / half_life;
+                if halvings >= 128 {
+                    0
+                } else {
+                    let step = excess >> halvings;
+                    step - (step / 2) * (elapsed % half_life) / half_life
+                }
+            }
+        };
+
+        self.floor_price + remaining as u64
+    }
+}
+
+impl Offer {
+    /// expiring listings have to be reclaimable by the permissionless `expire_listing` crank
+    pub fn validate_expiry(expires_at: Option<i64>, custody: Custody, now: i64) -> Result<()> {
+        if let Some(expires_at) = expires_at {
+            require!(
+                custody == Custody::Vault,
+                MarketplaceErrors::ExpiryNotSupported
+            );
+            require!(expires_at > now, MarketplaceErrors::InvalidExpiry);
+        }
+        Ok(())
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if now >= expires_at)
+    }
+
+    /// private listings can only be purchased by their reserved buyer
+    pub fn validate_buyer(&self, buyer: &Pubkey) -> Result<()> {
+        if let Some(allowed_buyer) = self.allowed_buyer {
+            require_keys_eq!(allowed_buyer, *buyer, MarketplaceErrors::BuyerNotAllowed);
+        }
+        Ok(())
+    }
+
+    pub fn emit_listed(&self, mint: Pubkey) -> Result<()> {
+        emit!(Listed {
+            seller: self.seller,
+            mint,
+            collection: self.collection,
+            price: self.price,
+            fee: self.fee,
+            expires_at: self.expires_at,
+            allowed_buyer: self.allowed_buyer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn emit_delisted(&self, mint: Pubkey, expired: bool) -> Result<()> {
+        emit!(Delisted {
+            seller: self.seller,
+            mint,
+            collection: self.collection,
+            expired,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// `price` and `fee` are the lamports actually paid, dutch auction prices differ from `self.price`
+    pub fn emit_purchased(&self, buyer: Pubkey, mint: Pubkey, price: u64, fee: u64) -> Result<()> {
+        emit!(Purchased {
+            buyer,
+            seller: self.seller,
+            mint,
+            collection: self.collection,
+            price,
+            fee,
+            royalties: None,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// pays the listing and purchase referrers their share of `fee` out of `buyer`, returns the total paid
+    /// the listing referrer is only required when the listing was made through one
+    pub fn pay_referrers<'info>(
+        &self,
+        fee: u64,
+        buyer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        listing_referrer: Option<&AccountInfo<'info>>,
+        referrer: Option<&Referrer>,
+        referrer_wallet: Option<&AccountInfo<'info>>,
+    ) -> Result<u64> {
+        let mut payouts = Vec::with_capacity(2);
+
+        if let Some(wallet) = self.referrer {
+            let listing_referrer = listing_referrer.ok_or(MarketplaceErrors::MissingReferrer)?;
+            require_keys_eq!(
+                listing_referrer.key(),
+                wallet,
+                MarketplaceErrors::ReferrerMismatch
+            );
+            payouts.push((listing_referrer, Referrer::cut(fee, self.referrer_share)));
+        }
+
+        if let Some(referrer) = referrer {
+            let referrer_wallet = referrer_wallet.ok_or(MarketplaceErrors::MissingReferrer)?;
+            require_keys_eq!(
+                referrer_wallet.key(),
+                referrer.wallet,
+                MarketplaceErrors::ReferrerMismatch
+            );
+            payouts.push((referrer_wallet, Referrer::cut(fee, referrer.share)));
+        }
+
+        let mut referral = 0;
+        for (wallet, cut) in payouts.into_iter().filter(|(_, cut)| *cut > 0) {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: buyer.clone(),
+                        to: wallet.clone(),
+                    },
+                ),
+                cut,
+            )?;
+            referral += cut;
+        }
+
+        Ok(referral)
+    }
+
+    /// price a buyer pays at `now`
+    pub fn current_price(&self, now: i64) -> u64 {
+        match self.decay {
+            Some(decay) => decay.price_at(self.price, now),
+            None => self.price,
+        }
+    }
+
+    /// price a buyer pays at `now`, bounded by what they signed for
+    /// sellers can raise the price with `update_listing`, so a pending purchase never pays more than `max_price`
+    pub fn price_for(&self, now: i64, max_price: u64) -> Result<u64> {
+        let price = self.current_price(now);
+        require!(price <= max_price, MarketplaceErrors::PriceAboveMax);
+        Ok(price)
+    }
+
+    pub fn emit_updated(&self, mint: Pubkey) -> Result<()> {
+        emit!(ListingUpdated {
+            seller: self.seller,
+            mint,
+            collection: self.collection,
+            price: self.price,
+            expires_at: self.expires_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedAction {
+    pub id: u64,
+    pub action: AdminAction,
+    pub proposer: Pubkey, // paid the rent, refunded when the action is executed or cancelled
+    pub proposed_at: i64,
+    pub executable_at: i64,
+    pub bump: u8,
+}
+
+/// admin operations that go through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    UpdateFee {
+        fee: u16,
+    },
+    SetTreasury {
+        treasury: Pubkey,
+    },
+    TransferAdmin {
+        admin: Pubkey,
+    },
+    SetCollectionFee {
+        collection_mint: Pubkey,
+        fee: Option<u16>,
+    }, // None falls back to protocol fee
+    SetReferrerShare {
+        wallet: Pubkey,
+        share: u16,
+    },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Referrer {
+    pub wallet: Pubkey, // partner frontend wallet receiving the referral share
+    pub share: u16,     // share of the protocol fee in bips, 2500 = a quarter of the fee
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Bid {
+    pub bidder: Pubkey,
+    pub target: Pubkey, // nft mint, or collection mint for collection-wide offers
+    pub collection: bool, // `target` is a verified collection, not a single nft
+    pub price: u64,     // lamports escrowed in this account on top of its rent
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey, // configured collection the nft is a verified member of
+    pub reserve_price: u64, // minimum first bid in lamports
+    pub min_increment: u64, // minimum raise over the current highest bid
+    pub start_time: i64,
+    pub end_time: i64,
+    pub highest_bid: u64, // lamports escrowed in this account on top of its rent
+    pub highest_bidder: Pubkey, // default pubkey until the first bid
+    pub fee: u16,         // fee in bips at auction creation, charged on settlement
     pub bump: u8,
 }
 
+impl CollectionConfig {
+    /// nft must be a verified member of this collection
+    pub fn validate_member(&self, metadata: &MetadataAccount) -> Result<()> {
+        let collection = metadata
+            .collection
+            .as_ref()
+            .ok_or(MarketplaceErrors::UnverifiedCollection)?;
+
+        require!(collection.verified, MarketplaceErrors::UnverifiedCollection);
+        require_keys_eq!(
+            collection.key,
+            self.collection_mint,
+            MarketplaceErrors::CollectionMismatch
+        );
+
+        Ok(())
+    }
+
+    /// fee in bips charged on this collection, override takes precedence over protocol fee
+    pub fn effective_fee(&self, global: &Global) -> u16 {
+        self.fee.unwrap_or(global.fee)
+    }
+
+    pub fn validate_trading(&self) -> Result<()> {
+        require!(self.enabled, MarketplaceErrors::CollectionDisabled);
+        require!(!self.frozen, MarketplaceErrors::CollectionFrozen);
+        Ok(())
+    }
+}
+
+impl AdminAction {
+    /// time between proposing and executing the action
+    pub fn delay(&self) -> i64 {
+        match self {
+            // sellers get 2 weeks notice before a new fee applies to their new listings
+            AdminAction::UpdateFee { .. } | AdminAction::SetCollectionFee { .. } => {
+                Global::TWO_WEEKS
+            }
+            AdminAction::SetTreasury { .. }
+            | AdminAction::TransferAdmin { .. }
+            | AdminAction::SetReferrerShare { .. } => Global::ONE_WEEK,
+        }
+    }
+}
+
+impl Referrer {
+    // listing and purchase referrers are paid out of the same fee, so each can take at most half
+    pub const MAX_SHARE: u16 = 5000;
+
+    /// lamports out of `fee` owed to a referrer with `share` bips
+    pub fn cut(fee: u64, share: u16) -> u64 {
+        fee.checked_mul(share as u64)
+            .unwrap()
+            .checked_div(10000)
+            .unwrap() // fee * sharebips / 10000
+    }
+}
+
+impl Rental {
+    pub const ONE_DAY: i64 = 24 * 60 * 60; // 86,400 seconds
+
+    /// lamports a renter pays for the whole duration
+    pub fn total_price(&self) -> u64 {
+        self.price_per_day.checked_mul(self.days as u64).unwrap() // price_per_day * days
+    }
+
+    pub fn is_over(&self, now: i64) -> bool {
+        self.renter.is_some() && now >= self.rented_until
+    }
+
+    pub fn emit_listed(&self) -> Result<()> {
+        emit!(RentalListed {
+            owner: self.owner,
+            mint: self.mint,
+            collection: self.collection,
+            price_per_day: self.price_per_day,
+            days: self.days,
+            fee: self.fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn emit_delisted(&self) -> Result<()> {
+        emit!(RentalDelisted {
+            owner: self.owner,
+            mint: self.mint,
+            collection: self.collection,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn emit_rented(&self, renter: Pubkey, price: u64, fee: u64) -> Result<()> {
+        emit!(Rented {
+            renter,
+            owner: self.owner,
+            mint: self.mint,
+            collection: self.collection,
+            price,
+            fee,
+            rented_until: self.rented_until,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn emit_reclaimed(&self, renter: Pubkey) -> Result<()> {
+        emit!(Reclaimed {
+            renter,
+            owner: self.owner,
+            mint: self.mint,
+            collection: self.collection,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+}
+
+impl Trade {
+    pub fn validate_size(offered: usize, requested: usize) -> Result<()> {
+        require!(
+            (1..=MAX_TRADE_NFTS).contains(&offered) && (1..=MAX_TRADE_NFTS).contains(&requested),
+            MarketplaceErrors::InvalidTrade
+        );
+        Ok(())
+    }
+
+    /// escrows `owner`'s nft of `mint` in its `[b"vault", mint]` pda, the same vault listings use
+    /// the vault is created here, paid by `owner`, unless it's still open
+    pub fn deposit_nft<'info>(
+        owner: &AccountInfo<'info>,
+        mint: &'info AccountInfo<'info>,
+        owner_ata: &'info AccountInfo<'info>,
+        vault: &'info AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        // vaults are shared with listings by mint, only one of a kind tokens can be escrowed in them
+        let mint_account = Account::<Mint>::try_from(mint)?;
+        require!(
+            mint_account.decimals == 0 && mint_account.supply == 1,
+            MarketplaceErrors::NotAnNft
+        );
+        Self::validate_token_account(owner_ata, owner.key(), mint.key())?;
+
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", mint.key.as_ref()], &crate::ID);
+        require_keys_eq!(
+            vault.key(),
+            vault_key,
+            MarketplaceErrors::InvalidTradeAccount
+        );
+
+        if vault.data_is_empty() {
+            create_pda_account(
+                owner,
+                vault,
+                TokenAccount::LEN,
+                token_program.key,
+                &[&[b"vault", mint.key.as_ref(), &[vault_bump]]],
+                system_program,
+            )?;
+
+            initialize_account3(CpiContext::new(
+                token_program.clone(),
+                InitializeAccount3 {
+                    account: vault.clone(),
+                    mint: mint.clone(),
+                    authority: vault.clone(),
+                },
+            ))?;
+        }
+
+        transfer_nft(
+            CpiContext::new(
+                token_program.clone(),
+                TransferNft {
+                    from: owner_ata.clone(),
+                    to: vault.clone(),
+                    authority: owner.clone(),
+                },
+            ),
+            1,
+        )
+    }
+
+    /// moves the escrowed nft of `mint` to `recipient`'s ata and closes its vault, rent goes to `rent_to`
+    pub fn release_nft<'info>(
+        mint: Pubkey,
+        vault: &'info AccountInfo<'info>,
+        recipient_ata: &'info AccountInfo<'info>,
+        recipient: Pubkey,
+        rent_to: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let (vault_key, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", mint.as_ref()], &crate::ID);
+        require_keys_eq!(
+            vault.key(),
+            vault_key,
+            MarketplaceErrors::InvalidTradeAccount
+        );
+        Self::validate_token_account(recipient_ata, recipient, mint)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"vault", mint.as_ref(), &[vault_bump]]];
+
+        // drain the whole vault, anything sent to it on top of the nft would otherwise block the close
+        let amount = Account::<TokenAccount>::try_from(vault)?.amount;
+
+        transfer_nft(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferNft {
+                    from: vault.clone(),
+                    to: recipient_ata.clone(),
+                    authority: vault.clone(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            token_program.clone(),
+            CloseAccount {
+                account: vault.clone(),
+                destination: rent_to.clone(),
+                authority: vault.clone(),
+            },
+            signer_seeds,
+        ))
+    }
+
+    /// nft of `mint` has to be a verified member of a tradeable configured collection, same as listing
+    /// returns the collection mint, to be recorded in `collections`
+    pub fn validate_collection<'info>(
+        mint: Pubkey,
+        metadata: &'info AccountInfo<'info>,
+        collection_config: &'info AccountInfo<'info>,
+    ) -> Result<Pubkey> {
+        let (metadata_key, _) = Pubkey::find_program_address(
+            &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+            &mpl_token_metadata::ID,
+        );
+        require_keys_eq!(
+            metadata.key(),
+            metadata_key,
+            MarketplaceErrors::InvalidTradeAccount
+        );
+        let metadata = Account::<MetadataAccount>::try_from(metadata)?;
+
+        let config = Account::<CollectionConfig>::try_from(collection_config)?;
+        Self::validate_collection_config(collection_config, config.collection_mint)?;
+        config.validate_member(&metadata)?;
+        config.validate_trading()?;
+
+        Ok(config.collection_mint)
+    }
+
+    /// `collection_config` has to be the config pda of `collection`
+    pub fn validate_collection_config(
+        collection_config: &AccountInfo,
+        collection: Pubkey,
+    ) -> Result<()> {
+        let (config_key, _) =
+            Pubkey::find_program_address(&[b"collection", collection.as_ref()], &crate::ID);
+        require_keys_eq!(
+            collection_config.key(),
+            config_key,
+            MarketplaceErrors::InvalidTradeAccount
+        );
+        Ok(())
+    }
+
+    pub fn add_collection(&mut self, collection: Pubkey) {
+        if !self.collections.contains(&collection) {
+            self.collections.push(collection);
+        }
+    }
+
+    pub fn validate_token_account<'info>(
+        account: &'info AccountInfo<'info>,
+        owner: Pubkey,
+        mint: Pubkey,
+    ) -> Result<()> {
+        let token = Account::<TokenAccount>::try_from(account)?;
+        require_keys_eq!(token.owner, owner, MarketplaceErrors::InvalidTradeAccount);
+        require_keys_eq!(token.mint, mint, MarketplaceErrors::InvalidTradeAccount);
+        Ok(())
+    }
+}
+
+impl Auction {
+    /// nft goes to the highest bidder, or back to the seller when nobody bid
+    pub fn recipient(&self) -> Pubkey {
+        if self.highest_bid > 0 {
+            self.highest_bidder
+        } else {
+            self.seller
+        }
+    }
+}
+
+/// current and pending protocol fees, returned by `get_fees`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeeSchedule {
+    pub fee: u16,
+    pub fee_effective_at: i64,
+    pub new_fee: Option<u16>,
+    pub new_fee_effective_at: Option<i64>,
+}
+
+/// leaf of a compressed nft, as fetched from the DAS api, needed to verify bubblegum transfers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompressedLeaf {
+    pub root: [u8; 32],
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+    pub nonce: u64,
+    pub index: u32,
+}
+
+impl CompressedLeaf {
+    pub fn asset_id(&self, merkle_tree: &Pubkey) -> Pubkey {
+        mpl_bubblegum::utils::get_asset_id(merkle_tree, self.nonce)
+    }
+
+    /// verified collection of the leaf, `metadata` is the borsh encoded bubblegum `MetadataArgs` behind `data_hash`
+    pub fn verified_collection(&self, metadata: &[u8]) -> Result<Pubkey> {
+        let metadata =
+            MetadataArgs::try_from_slice(metadata).map_err(|_| MarketplaceErrors::InvalidAsset)?;
+        require!(
+            hash_metadata(&metadata)? == self.data_hash,
+            MarketplaceErrors::MetadataHashMismatch
+        );
+
+        let collection = metadata
+            .collection
+            .filter(|collection| collection.verified)
+            .ok_or(MarketplaceErrors::UnverifiedCollection)?;
+
+        Ok(collection.key)
+    }
+
+    pub fn transfer_args(&self) -> mpl_bubblegum::instructions::TransferInstructionArgs {
+        mpl_bubblegum::instructions::TransferInstructionArgs {
+            root: self.root,
+            data_hash: self.data_hash,
+            creator_hash: self.creator_hash,
+            nonce: self.nonce,
+            index: self.index,
+        }
+    }
+}
+
+impl Global {
+    pub const ONE_WEEK: i64 = 7 * 24 * 60 * 60; // 604,800 seconds
+    pub const TWO_WEEKS: i64 = 2 * 7 * 24 * 60 * 60; // 1,209,600 seconds
+
+    /// while frozen, assets can only be pulled out of the protocol after 1 week delay
+    pub fn check_frozen_withdrawal(&self) -> Result<()> {
+        if self.frozen {
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                now - self.frozen_at >= Self::ONE_WEEK,
+                MarketplaceErrors::FrozenDelistDelay
+            );
+        }
+        Ok(())
+    }
+
+    /// `fee_action` is the queued `UpdateFee` action, None if no fee change is pending
+    pub fn fee_schedule(&self, fee_action: Option<&QueuedAction>) -> FeeSchedule {
+        let pending = fee_action.and_then(|queued| match queued.action {
+            AdminAction::UpdateFee { fee } => Some((fee, queued.executable_at)),
+            _ => None,
+        });
+
+        FeeSchedule {
+            fee: self.fee,
+            fee_effective_at: self.fee_effective_at,
+            new_fee: pending.map(|(fee, _)| fee),
+            new_fee_effective_at: pending.map(|(_, executable_at)| executable_at),
+        }
+    }
+}
+
 // user sell nft --->
@@ -0,0 +1,239 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use marketplace::error::MarketplaceErrors;
+use solana_sdk::signature::Signer;
+
+const DURATION: i64 = 3600;
+const INCREMENT: u64 = PRICE / 10;
+
+/// starts a one hour auction of the seller's nft with `PRICE` as reserve
+async fn create_auction(env: &mut Env) {
+    let now = env.now().await;
+    let seller = env.seller.insecure_clone();
+    env.process(
+        &[env.create_auction_ix(PRICE, INCREMENT, now, now + DURATION)],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+}
+
+async fn bid(env: &mut Env, previous_bidder: Option<Pubkey>, amount: u64) {
+    let bidder = env.buyer.insecure_clone();
+    env.process(
+        &[env.auction_bid_ix(bidder.pubkey(), previous_bidder, amount)],
+        &[&bidder],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn create_auction_moves_nft_into_vault() {
+    let mut env = Env::new().await;
+
+    create_auction(&mut env).await;
+
+    let auction = env.auction().await.unwrap();
+    assert_eq!(auction.seller, env.seller.pubkey());
+    assert_eq!(auction.collection, env.collection_mint);
+    assert_eq!(auction.reserve_price, PRICE);
+    assert_eq!(auction.highest_bid, 0);
+    assert_eq!(auction.fee, FEE);
+
+    let (seller_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(seller_ata).await, Some(0));
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
+
+#[tokio::test]
+async fn outbidding_refunds_previous_bidder() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+
+    // admin opens, buyer outbids
+    let admin = env.admin.pubkey();
+    env.process(&[env.auction_bid_ix(admin, None, PRICE)], &[])
+        .await
+        .unwrap();
+    let auction_before = env.lamports(env.auction_address()).await;
+    let admin_before = env.lamports(admin).await;
+
+    bid(&mut env, Some(admin), PRICE + INCREMENT).await;
+
+    let auction = env.auction().await.unwrap();
+    assert_eq!(auction.highest_bidder, env.buyer.pubkey());
+    assert_eq!(auction.highest_bid, PRICE + INCREMENT);
+    assert_eq!(
+        env.lamports(env.auction_address()).await,
+        auction_before + INCREMENT
+    );
+    // the admin paid the transaction fee, so only a lower bound holds
+    assert!(env.lamports(admin).await > admin_before + PRICE - PRICE / 100);
+}
+
+#[tokio::test]
+async fn auction_bid_rejects_low_bids() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+    let bidder = env.buyer.insecure_clone();
+
+    let result = env
+        .process(
+            &[env.auction_bid_ix(bidder.pubkey(), None, PRICE - 1)],
+            &[&bidder],
+        )
+        .await;
+    assert_error(result, MarketplaceErrors::BidTooLow);
+
+    // a raise has to clear the minimum increment
+    let admin = env.admin.pubkey();
+    env.process(&[env.auction_bid_ix(admin, None, PRICE)], &[])
+        .await
+        .unwrap();
+    let result = env
+        .process(
+            &[env.auction_bid_ix(bidder.pubkey(), Some(admin), PRICE + INCREMENT - 1)],
+            &[&bidder],
+        )
+        .await;
+    assert_error(result, MarketplaceErrors::BidTooLow);
+}
+
+#[tokio::test]
+async fn seller_cannot_bid() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+
+    let seller = env.seller.insecure_clone();
+    let result = env
+        .process(
+            &[env.auction_bid_ix(seller.pubkey(), None, PRICE)],
+            &[&seller],
+        )
+        .await;
+    assert_error(result, MarketplaceErrors::SellerCannotBid);
+}
+
+#[tokio::test]
+async fn settle_auction_pays_seller_and_delivers_nft() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+    bid(&mut env, None, PRICE).await;
+
+    let buyer = env.buyer.pubkey();
+    let result = env.process(&[env.settle_auction_ix(buyer)], &[]).await;
+    assert_error(result, MarketplaceErrors::AuctionNotEnded);
+
+    let seller = env.seller.pubkey();
+    let treasury = env.treasury;
+    let seller_before = env.lamports(seller).await;
+    let treasury_before = env.lamports(treasury).await;
+    let auction_rent = env.lamports(env.auction_address()).await - PRICE;
+    let vault_rent = env.lamports(env.vault_address()).await;
+
+    env.warp(DURATION).await;
+    env.process(&[env.settle_auction_ix(buyer)], &[])
+        .await
+        .unwrap();
+
+    let fee = PRICE * FEE as u64 / 10000;
+    assert_eq!(env.lamports(treasury).await, treasury_before + fee);
+    assert_eq!(
+        env.lamports(seller).await,
+        seller_before + PRICE - fee + auction_rent + vault_rent
+    );
+
+    let (buyer_ata, vault) = (env.buyer_ata(), env.vault_address());
+    assert_eq!(env.token_amount(buyer_ata).await, Some(1));
+    assert!(env.account(vault).await.is_none());
+    assert!(env.auction().await.is_none());
+}
+
+#[tokio::test]
+async fn settle_unsold_auction_returns_nft() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+    env.warp(DURATION).await;
+
+    let seller = env.seller.pubkey();
+    env.process(&[env.settle_auction_ix(seller)], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_amount(env.seller_ata()).await, Some(1));
+    assert!(env.auction().await.is_none());
+}
+
+#[tokio::test]
+async fn refund_auction_unwinds_after_freeze_delay() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+    let buyer = env.buyer.pubkey();
+    let buyer_before = env.lamports(buyer).await;
+    bid(&mut env, None, PRICE).await;
+
+    let result = env
+        .process(&[env.refund_auction_ix(Some(buyer))], &[])
+        .await;
+    assert_error(result, MarketplaceErrors::ProtocolNotFrozen);
+
+    env.freeze_thaw(true).await.unwrap();
+    env.warp(DURATION).await;
+    let result = env.process(&[env.settle_auction_ix(buyer)], &[]).await;
+    assert_error(result, MarketplaceErrors::ProtocolFrozen);
+
+    env.warp(ONE_WEEK).await;
+    env.process(&[env.refund_auction_ix(Some(buyer))], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(buyer).await, buyer_before);
+    assert_eq!(env.token_amount(env.seller_ata()).await, Some(1));
+    assert!(env.auction().await.is_none());
+}
+
+#[tokio::test]
+async fn create_auction_rejects_disabled_collection() {
+    let mut env = Env::new().await;
+    env.process(&[env.set_collection_ix(false)], &[])
+        .await
+        .unwrap();
+
+    let now = env.now().await;
+    let seller = env.seller.insecure_clone();
+    let result = env
+        .process(
+            &[env.create_auction_ix(PRICE, INCREMENT, now, now + DURATION)],
+            &[&seller],
+        )
+        .await;
+    assert_error(result, MarketplaceErrors::CollectionDisabled);
+}
+
+#[tokio::test]
+async fn disabled_collection_blocks_settling_and_allows_refund() {
+    let mut env = Env::new().await;
+    create_auction(&mut env).await;
+    let buyer = env.buyer.pubkey();
+    let buyer_before = env.lamports(buyer).await;
+    bid(&mut env, None, PRICE).await;
+
+    env.process(&[env.set_collection_ix(false)], &[])
+        .await
+        .unwrap();
+    env.warp(DURATION).await;
+    let result = env.process(&[env.settle_auction_ix(buyer)], &[]).await;
+    assert_error(result, MarketplaceErrors::CollectionDisabled);
+
+    // no freeze delay, the protocol itself isn't frozen
+    env.process(&[env.refund_auction_ix(Some(buyer))], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(buyer).await, buyer_before);
+    assert_eq!(env.token_amount(env.seller_ata()).await, Some(1));
+    assert!(env.auction().await.is_none());
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+
+#[tokio::test]
+async fn batch_list_and_delist() {
+    let mut env = Env::new().await;
+    let seller = env.seller.insecure_clone();
+
+    env.process(&[env.batch_list_ix(PRICE)], &[&seller])
+        .await
+        .unwrap();
+
+    let listing = env.listing().await.unwrap();
+    assert_eq!(listing.price, PRICE);
+    assert_eq!(listing.fee, FEE);
+    assert_eq!(env.token_amount(env.vault_address()).await, Some(1));
+
+    env.process(&[env.batch_delist_ix()], &[&seller])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_amount(env.seller_ata()).await, Some(1));
+    assert!(env.account(env.vault_address()).await.is_none());
+    assert!(env.listing().await.is_none());
+}
+
+#[tokio::test]
+async fn batch_list_takes_over_prefunded_accounts() {
+    let mut env = Env::new().await;
+    let seller = env.seller.insecure_clone();
+
+    // dust sent to the listing and vault addresses mustn't keep the nft from being listed
+    let (listing, vault) = (env.listing_address(), env.vault_address());
+    env.fund(listing, 1);
+    env.fund(vault, 1);
+
+    env.process(&[env.batch_list_ix(PRICE)], &[&seller])
+        .await
+        .unwrap();
+
+    assert_eq!(env.listing().await.unwrap().price, PRICE);
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
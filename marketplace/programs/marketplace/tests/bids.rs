@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+use marketplace::error::MarketplaceErrors;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn place_bid_escrows_lamports() {
+    let mut env = Env::new().await;
+    let bidder = env.buyer.insecure_clone();
+
+    env.process(&[env.place_bid_ix(env.mint, PRICE, false)], &[&bidder])
+        .await
+        .unwrap();
+
+    let bid = env.bid(env.mint).await.unwrap();
+    assert_eq!(bid.bidder, bidder.pubkey());
+    assert_eq!(bid.target, env.mint);
+    assert!(!bid.collection);
+    assert_eq!(bid.price, PRICE);
+
+    let bid_account = env.account(env.bid_address(env.mint)).await.unwrap();
+    let rent = solana_sdk::rent::Rent::default().minimum_balance(bid_account.data.len());
+    assert_eq!(bid_account.lamports, rent + PRICE);
+}
+
+#[tokio::test]
+async fn cancel_bid_refunds_bidder() {
+    let mut env = Env::new().await;
+    let bidder = env.buyer.insecure_clone();
+    let bidder_before = env.lamports(bidder.pubkey()).await;
+    env.process(&[env.place_bid_ix(env.mint, PRICE, false)], &[&bidder])
+        .await
+        .unwrap();
+
+    env.process(&[env.cancel_bid_ix(env.mint)], &[&bidder])
+        .await
+        .unwrap();
+
+    // price and rent are refunded, fees are paid by the admin
+    assert_eq!(env.lamports(bidder.pubkey()).await, bidder_before);
+    assert!(env.bid(env.mint).await.is_none());
+}
+
+#[tokio::test]
+async fn accept_bid_pays_seller_and_treasury() {
+    let mut env = Env::new().await;
+    let (seller, bidder) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    env.process(&[env.place_bid_ix(env.mint, PRICE, false)], &[&bidder])
+        .await
+        .unwrap();
+
+    let treasury = env.treasury;
+    let seller_before = env.lamports(seller.pubkey()).await;
+    let bidder_before = env.lamports(bidder.pubkey()).await;
+    let treasury_before = env.lamports(treasury).await;
+    let bid_lamports = env.lamports(env.bid_address(env.mint)).await;
+
+    env.process(&[env.accept_bid_ix(env.mint)], &[&seller])
+        .await
+        .unwrap();
+
+    let fee = PRICE * FEE as u64 / 10000;
+    assert_eq!(env.lamports(treasury).await, treasury_before + fee);
+    assert_eq!(
+        env.lamports(seller.pubkey()).await,
+        seller_before + PRICE - fee
+    );
+    // bid rent goes back to the bidder
+    assert_eq!(
+        env.lamports(bidder.pubkey()).await,
+        bidder_before + bid_lamports - PRICE
+    );
+
+    let (seller_ata, buyer_ata) = (env.seller_ata(), env.buyer_ata());
+    assert_eq!(env.token_amount(seller_ata).await, Some(0));
+    assert_eq!(env.token_amount(buyer_ata).await, Some(1));
+    assert!(env.bid(env.mint).await.is_none());
+}
+
+#[tokio::test]
+async fn accept_collection_bid_takes_any_member() {
+    let mut env = Env::new().await;
+    let (seller, bidder) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    let collection = env.collection_mint;
+    env.process(&[env.place_bid_ix(collection, PRICE, true)], &[&bidder])
+        .await
+        .unwrap();
+
+    env.process(&[env.accept_bid_ix(collection)], &[&seller])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_amount(env.buyer_ata()).await, Some(1));
+    assert!(env.bid(collection).await.is_none());
+}
+
+#[tokio::test]
+async fn accept_bid_rejects_other_nft() {
+    let mut env = Env::new().await;
+    let (seller, bidder) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    // bid on a single nft can't be filled with another one from the same collection
+    let other = env.buyer_mint;
+    env.process(&[env.place_bid_ix(other, PRICE, false)], &[&bidder])
+        .await
+        .unwrap();
+
+    let result = env.process(&[env.accept_bid_ix(other)], &[&seller]).await;
+    assert_error(result, MarketplaceErrors::BidTargetMismatch);
+}
+
+#[tokio::test]
+async fn frozen_protocol_rejects_accepting_bid() {
+    let mut env = Env::new().await;
+    let (seller, bidder) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    env.process(&[env.place_bid_ix(env.mint, PRICE, false)], &[&bidder])
+        .await
+        .unwrap();
+    env.freeze_thaw(true).await.unwrap();
+
+    let result = env
+        .process(&[env.accept_bid_ix(env.mint)], &[&seller])
+        .await;
+    assert_error(result, MarketplaceErrors::ProtocolFrozen);
+}
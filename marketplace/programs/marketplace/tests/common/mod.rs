@@ -0,0 +1,1096 @@
+#![allow(dead_code)]
+
+use anchor_lang::{
+    prelude::*, solana_program::program_pack::Pack, system_program, AccountDeserialize,
+    AccountSerialize, InstructionData,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address},
+    metadata::mpl_token_metadata,
+    token::spl_token,
+};
+use borsh::BorshSerialize;
+use marketplace::{
+    error::MarketplaceErrors, AdminAction, Auction, Bid, CollectionConfig, DutchAuction,
+    FeeSchedule, Global, Offer, Rental, Trade,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::{Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+pub const FEE: u16 = 50; // 0.5%
+pub const PRICE: u64 = 1_000_000_000;
+pub const ONE_WEEK: i64 = Global::ONE_WEEK;
+pub const TWO_WEEKS: i64 = Global::TWO_WEEKS;
+
+// anchor's entrypoint ties the accounts slice to the `'info` lifetime, which doesn't fit `processor!`
+fn entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    marketplace::entry(program_id, accounts, data)
+}
+
+pub struct Env {
+    pub ctx: ProgramTestContext,
+    pub admin: Keypair,
+    pub treasury: Pubkey,
+    pub seller: Keypair,
+    pub buyer: Keypair,
+    pub mint: Pubkey,
+    pub buyer_mint: Pubkey, // nft held by the buyer, asked for in trades
+    pub collection_mint: Pubkey,
+}
+
+impl Env {
+    /// initialized marketplace with an enabled collection, a seller holding one nft of it
+    /// and a buyer holding another nft
+    pub async fn new() -> Self {
+        let mut test = ProgramTest::new("marketplace", marketplace::ID, processor!(entry));
+        test.prefer_bpf(false);
+
+        let admin = Keypair::new();
+        let seller = Keypair::new();
+        let buyer = Keypair::new();
+        let treasury = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let buyer_mint = Pubkey::new_unique();
+        let collection_mint = Pubkey::new_unique();
+
+        for wallet in [admin.pubkey(), seller.pubkey(), buyer.pubkey(), treasury] {
+            test.add_account(wallet, system_account(10 * PRICE));
+        }
+        test.add_account(mint, mint_account(1));
+        test.add_account(collection_mint, mint_account(1));
+        test.add_account(
+            get_associated_token_address(&seller.pubkey(), &mint),
+            token_account(&mint, &seller.pubkey(), 1),
+        );
+        test.add_account(
+            get_associated_token_address(&buyer.pubkey(), &mint),
+            token_account(&mint, &buyer.pubkey(), 0),
+        );
+        test.add_account(buyer_mint, mint_account(1));
+        test.add_account(
+            get_associated_token_address(&buyer.pubkey(), &buyer_mint),
+            token_account(&buyer_mint, &buyer.pubkey(), 1),
+        );
+        test.add_account(
+            get_associated_token_address(&seller.pubkey(), &buyer_mint),
+            token_account(&buyer_mint, &seller.pubkey(), 0),
+        );
+        test.add_account(
+            metadata_address(&mint),
+            metadata_account(&mint, &collection_mint),
+        );
+        test.add_account(
+            metadata_address(&buyer_mint),
+            metadata_account(&buyer_mint, &collection_mint),
+        );
+        // list/delist/purchase only require token metadata to be an executable account, it's never invoked
+        test.add_account(
+            mpl_token_metadata::ID,
+            Account {
+                lamports: 1,
+                data: vec![],
+                owner: solana_sdk::bpf_loader::ID,
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+
+        let ctx = test.start_with_context().await;
+
+        let mut env = Env {
+            ctx,
+            admin,
+            treasury,
+            seller,
+            buyer,
+            mint,
+            buyer_mint,
+            collection_mint,
+        };
+
+        env.process(&[env.initialize_ix(FEE)], &[]).await.unwrap();
+        env.process(&[env.set_collection_ix(true)], &[])
+            .await
+            .unwrap();
+
+        env
+    }
+
+    /// sends `ixs` paid by the admin, with `signers` on top of it
+    pub async fn process(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+
+        let mut all_signers = vec![&self.admin];
+        all_signers.extend_from_slice(signers);
+
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.admin.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// simulates `get_fees`, `fee_action` is the id of the queued fee update, if any
+    pub async fn fee_schedule(&mut self, fee_action: Option<u64>) -> FeeSchedule {
+        let ix = instruction(
+            marketplace::accounts::GetFees {
+                global: global_address(),
+                fee_action: fee_action.map(action_address),
+            },
+            marketplace::instruction::GetFees {},
+        );
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.admin.pubkey()),
+            &[&self.admin],
+            blockhash,
+        );
+
+        let simulation = self
+            .ctx
+            .banks_client
+            .simulate_transaction(tx)
+            .await
+            .unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        FeeSchedule::try_from_slice(&return_data.data).unwrap()
+    }
+
+    /// moves the clock forward by `seconds`
+    pub async fn warp(&mut self, seconds: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    /// overwrites the supply of `mint`, turning the nft into a fungible-looking mint
+    pub fn set_mint_supply(&mut self, mint: Pubkey, supply: u64) {
+        self.ctx.set_account(&mint, &mint_account(supply).into());
+    }
+
+    /// lamports sent to `address` by a third party, e.g. to squat a pda before it's created
+    pub fn fund(&mut self, address: Pubkey, lamports: u64) {
+        self.ctx
+            .set_account(&address, &system_account(lamports).into());
+    }
+
+    /// overwrites the listing at `address`, for listings the test validator can't create itself
+    pub fn set_listing(&mut self, address: Pubkey, listing: &Offer) {
+        let mut data = Vec::with_capacity(8 + Offer::INIT_SPACE);
+        listing.try_serialize(&mut data).unwrap();
+        data.resize(8 + Offer::INIT_SPACE, 0);
+        self.ctx
+            .set_account(&address, &rent_exempt(data, marketplace::ID).into());
+    }
+
+    pub async fn account(&mut self, address: Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(address).await.unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.account(address)
+            .await
+            .map_or(0, |account| account.lamports)
+    }
+
+    pub async fn token_amount(&mut self, address: Pubkey) -> Option<u64> {
+        let account = self.account(address).await?;
+        Some(
+            spl_token::state::Account::unpack(&account.data)
+                .unwrap()
+                .amount,
+        )
+    }
+
+    pub async fn global(&mut self) -> Global {
+        let account = self.account(global_address()).await.unwrap();
+        Global::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn listing(&mut self) -> Option<Offer> {
+        self.listing_at(self.listing_address()).await
+    }
+
+    pub async fn listing_at(&mut self, address: Pubkey) -> Option<Offer> {
+        let account = self.account(address).await?;
+        Some(Offer::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn listing_address(&self) -> Pubkey {
+        self.asset_listing_address(&self.mint).0
+    }
+
+    /// seller's listing of `asset`, a mint, core asset or compressed asset id
+    pub fn asset_listing_address(&self, asset: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"listing", self.seller.pubkey().as_ref(), asset.as_ref()],
+            &marketplace::ID,
+        )
+    }
+
+    pub async fn rental(&mut self) -> Option<Rental> {
+        let account = self.account(self.rental_address()).await?;
+        Some(Rental::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn rental_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"rental", self.seller.pubkey().as_ref(), self.mint.as_ref()],
+            &marketplace::ID,
+        )
+        .0
+    }
+
+    pub fn vault_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault", self.mint.as_ref()], &marketplace::ID).0
+    }
+
+    /// vault escrowing the buyer's nft in trades
+    pub fn buyer_vault_address(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault", self.buyer_mint.as_ref()], &marketplace::ID).0
+    }
+
+    pub fn buyer_mint_ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address(owner, &self.buyer_mint)
+    }
+
+    pub fn seller_ata(&self) -> Pubkey {
+        get_associated_token_address(&self.seller.pubkey(), &self.mint)
+    }
+
+    pub fn buyer_ata(&self) -> Pubkey {
+        get_associated_token_address(&self.buyer.pubkey(), &self.mint)
+    }
+
+    pub async fn trade(&mut self, id: u64) -> Option<Trade> {
+        let account = self.account(self.trade_address(id)).await?;
+        Some(Trade::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn trade_address(&self, id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"trade", self.seller.pubkey().as_ref(), &id.to_le_bytes()],
+            &marketplace::ID,
+        )
+        .0
+    }
+
+    pub async fn bid(&mut self, target: Pubkey) -> Option<Bid> {
+        let account = self.account(self.bid_address(target)).await?;
+        Some(Bid::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    /// bid of the buyer on `target`
+    pub fn bid_address(&self, target: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"bid", self.buyer.pubkey().as_ref(), target.as_ref()],
+            &marketplace::ID,
+        )
+        .0
+    }
+
+    pub async fn auction(&mut self) -> Option<Auction> {
+        let account = self.account(self.auction_address()).await?;
+        Some(Auction::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    pub fn auction_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"auction",
+                self.seller.pubkey().as_ref(),
+                self.mint.as_ref(),
+            ],
+            &marketplace::ID,
+        )
+        .0
+    }
+
+    pub async fn collection_config(&mut self) -> CollectionConfig {
+        let account = self
+            .account(self.collection_config_address())
+            .await
+            .unwrap();
+        CollectionConfig::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn collection_config_address(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"collection", self.collection_mint.as_ref()],
+            &marketplace::ID,
+        )
+        .0
+    }
+
+    //=====================
+    //    admin instructions
+    //=====================
+
+    pub fn initialize_ix(&self, fee: u16) -> Instruction {
+        instruction(
+            marketplace::accounts::Initialize {
+                admin: self.admin.pubkey(),
+                global: global_address(),
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::Initialize {
+                treasury: self.treasury,
+                fee,
+            },
+        )
+    }
+
+    pub fn set_collection_ix(&self, enabled: bool) -> Instruction {
+        instruction(
+            marketplace::accounts::SetCollection {
+                admin: self.admin.pubkey(),
+                global: global_address(),
+                collection_mint: self.collection_mint,
+                collection_config: self.collection_config_address(),
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::SetCollection {
+                enabled,
+                frozen: false,
+            },
+        )
+    }
+
+    pub fn freeze_thaw_ix(&self, freeze: bool) -> Instruction {
+        instruction(
+            marketplace::accounts::FreezeThaw {
+                admin: self.admin.pubkey(),
+                global: global_address(),
+            },
+            marketplace::instruction::FreezeThaw { freeze },
+        )
+    }
+
+    pub fn propose_action_ix(&self, id: u64, action: AdminAction) -> Instruction {
+        instruction(
+            marketplace::accounts::ProposeAction {
+                admin: self.admin.pubkey(),
+                global: global_address(),
+                queued_action: action_address(id),
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::ProposeAction { action },
+        )
+    }
+
+    pub fn execute_action_ix(&self, id: u64) -> Instruction {
+        self.execute_action_ix_with(id, None)
+    }
+
+    pub fn execute_action_ix_with(
+        &self,
+        id: u64,
+        collection_config: Option<Pubkey>,
+    ) -> Instruction {
+        instruction(
+            marketplace::accounts::ExecuteAction {
+                global: global_address(),
+                queued_action: action_address(id),
+                proposer: self.admin.pubkey(),
+                collection_config,
+                referrer: None,
+            },
+            marketplace::instruction::ExecuteAction {},
+        )
+    }
+
+    pub fn cancel_action_ix(&self, id: u64) -> Instruction {
+        instruction(
+            marketplace::accounts::CancelAction {
+                admin: self.admin.pubkey(),
+                global: global_address(),
+                queued_action: action_address(id),
+                proposer: self.admin.pubkey(),
+            },
+            marketplace::instruction::CancelAction {},
+        )
+    }
+
+    //=====================
+    //    user instructions
+    //=====================
+
+    pub fn list_ix(&self, price: u64) -> Instruction {
+        self.list_ix_with(price, None, None)
+    }
+
+    pub fn list_ix_with(
+        &self,
+        price: u64,
+        expires_at: Option<i64>,
+        allowed_buyer: Option<Pubkey>,
+    ) -> Instruction {
+        instruction(
+            marketplace::accounts::List {
+                seller: self.seller.pubkey(),
+                mint: self.mint,
+                collection_mint: self.collection_mint,
+                seller_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                listing: self.listing_address(),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                referrer: None,
+                metadata: metadata_address(&self.mint),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::ListNft {
+                price,
+                expires_at,
+                allowed_buyer,
+            },
+        )
+    }
+
+    pub fn list_dutch_ix(&self, start_price: u64, decay: DutchAuction) -> Instruction {
+        let mut ix = self.list_ix(start_price);
+        ix.data = marketplace::instruction::ListNftDutch {
+            start_price,
+            decay,
+            expires_at: None,
+        }
+        .data();
+        ix
+    }
+
+    /// expiry crank, paid by the admin
+    pub fn expire_listing_ix(&self) -> Instruction {
+        instruction(
+            marketplace::accounts::ExpireListing {
+                payer: self.admin.pubkey(),
+                seller: self.seller.pubkey(),
+                mint: self.mint,
+                seller_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                listing: self.listing_address(),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::ExpireListing {},
+        )
+    }
+
+    pub fn delist_ix(&self) -> Instruction {
+        instruction(
+            marketplace::accounts::Delist {
+                seller: self.seller.pubkey(),
+                mint: self.mint,
+                seller_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                listing: self.listing_address(),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::DelistNft {},
+        )
+    }
+
+    /// seller lists `mint` in a batch of one
+    pub fn batch_list_ix(&self, price: u64) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::BatchList {
+                seller: self.seller.pubkey(),
+                collection_mint: self.collection_mint,
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+            },
+            marketplace::instruction::BatchList {
+                prices: vec![price],
+                expires_at: None,
+            },
+        );
+        ix.accounts.extend([
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.seller_ata(), false),
+            AccountMeta::new(self.vault_address(), false),
+            AccountMeta::new(self.listing_address(), false),
+            AccountMeta::new_readonly(metadata_address(&self.mint), false),
+        ]);
+        ix
+    }
+
+    /// seller delists `mint` in a batch of one
+    pub fn batch_delist_ix(&self) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::BatchDelist {
+                seller: self.seller.pubkey(),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::BatchDelist {},
+        );
+        ix.accounts.extend([
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.seller_ata(), false),
+            AccountMeta::new(self.vault_address(), false),
+            AccountMeta::new(self.listing_address(), false),
+        ]);
+        ix
+    }
+
+    pub fn purchase_ix(&self, treasury: Pubkey) -> Instruction {
+        self.purchase_ix_with(treasury, u64::MAX)
+    }
+
+    pub fn purchase_ix_with(&self, treasury: Pubkey, max_price: u64) -> Instruction {
+        instruction(
+            marketplace::accounts::Purchase {
+                buyer: self.buyer.pubkey(),
+                seller: self.seller.pubkey(),
+                mint: self.mint,
+                buyer_ata: self.buyer_ata(),
+                seller_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                listing: self.listing_address(),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                treasury,
+                referrer: None,
+                referrer_wallet: None,
+                listing_referrer: None,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::PurchaseNft { max_price },
+        )
+    }
+
+    pub fn update_listing_ix(&self, price: u64, expires_at: Option<i64>) -> Instruction {
+        self.update_asset_listing_ix(self.mint, price, expires_at)
+    }
+
+    pub fn update_asset_listing_ix(
+        &self,
+        asset: Pubkey,
+        price: u64,
+        expires_at: Option<i64>,
+    ) -> Instruction {
+        instruction(
+            marketplace::accounts::UpdateListing {
+                seller: self.seller.pubkey(),
+                asset,
+                listing: self.asset_listing_address(&asset).0,
+                global: global_address(),
+            },
+            marketplace::instruction::UpdateListing { price, expires_at },
+        )
+    }
+
+    pub fn list_rental_ix(&self, price_per_day: u64, days: u16) -> Instruction {
+        instruction(
+            marketplace::accounts::ListRental {
+                owner: self.seller.pubkey(),
+                mint: self.mint,
+                collection_mint: self.collection_mint,
+                owner_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                rental: self.rental_address(),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                metadata: metadata_address(&self.mint),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::ListRental {
+                price_per_day,
+                days,
+            },
+        )
+    }
+
+    pub fn delist_rental_ix(&self) -> Instruction {
+        instruction(
+            marketplace::accounts::DelistRental {
+                owner: self.seller.pubkey(),
+                mint: self.mint,
+                owner_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                rental: self.rental_address(),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::DelistRental {},
+        )
+    }
+
+    /// seller trades `mint` and `lamports` for the buyer's `buyer_mint`
+    pub fn propose_trade_ix(&self, id: u64, lamports: u64) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::ProposeTrade {
+                maker: self.seller.pubkey(),
+                taker: self.buyer.pubkey(),
+                trade: self.trade_address(id),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::ProposeTrade {
+                id,
+                requested: vec![self.buyer_mint],
+                lamports,
+            },
+        );
+        ix.accounts.extend([
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.seller_ata(), false),
+            AccountMeta::new(self.vault_address(), false),
+            AccountMeta::new_readonly(metadata_address(&self.mint), false),
+            AccountMeta::new_readonly(self.collection_config_address(), false),
+        ]);
+        ix
+    }
+
+    /// `deposited` adds the taker's escrowed nft to be returned as well
+    pub fn cancel_trade_ix(&self, id: u64, deposited: bool) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::CancelTrade {
+                maker: self.seller.pubkey(),
+                taker: self.buyer.pubkey(),
+                trade: self.trade_address(id),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::CancelTrade {},
+        );
+        ix.accounts.extend([
+            AccountMeta::new(self.vault_address(), false),
+            AccountMeta::new(self.seller_ata(), false),
+        ]);
+        if deposited {
+            ix.accounts.extend([
+                AccountMeta::new(self.buyer_vault_address(), false),
+                AccountMeta::new(self.buyer_mint_ata(&self.buyer.pubkey()), false),
+            ]);
+        }
+        ix
+    }
+
+    /// buyer escrows the requested nft
+    pub fn deposit_trade_ix(&self, id: u64) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::DepositTrade {
+                taker: self.buyer.pubkey(),
+                maker: self.seller.pubkey(),
+                trade: self.trade_address(id),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::DepositTrade {},
+        );
+        ix.accounts.extend([
+            AccountMeta::new_readonly(self.buyer_mint, false),
+            AccountMeta::new(self.buyer_mint_ata(&self.buyer.pubkey()), false),
+            AccountMeta::new(self.buyer_vault_address(), false),
+            AccountMeta::new_readonly(metadata_address(&self.buyer_mint), false),
+            AccountMeta::new_readonly(self.collection_config_address(), false),
+        ]);
+        ix
+    }
+
+    pub fn withdraw_trade_ix(&self, id: u64) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::WithdrawTrade {
+                taker: self.buyer.pubkey(),
+                maker: self.seller.pubkey(),
+                trade: self.trade_address(id),
+                global: global_address(),
+                token_program: spl_token::ID,
+            },
+            marketplace::instruction::WithdrawTrade {},
+        );
+        ix.accounts.extend([
+            AccountMeta::new(self.buyer_vault_address(), false),
+            AccountMeta::new(self.buyer_mint_ata(&self.buyer.pubkey()), false),
+        ]);
+        ix
+    }
+
+    pub fn accept_trade_ix(&self, id: u64, taker: Pubkey) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::AcceptTrade {
+                taker,
+                maker: self.seller.pubkey(),
+                trade: self.trade_address(id),
+                global: global_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::AcceptTrade {},
+        );
+        ix.accounts.extend([
+            // both nfts are in the env's collection
+            AccountMeta::new_readonly(self.collection_config_address(), false),
+            AccountMeta::new(self.vault_address(), false),
+            AccountMeta::new(self.buyer_ata(), false),
+            AccountMeta::new(self.buyer_vault_address(), false),
+            AccountMeta::new(self.buyer_mint_ata(&self.seller.pubkey()), false),
+        ]);
+        ix
+    }
+
+    /// buyer bids on `target`, the nft mint or the collection mint
+    pub fn place_bid_ix(&self, target: Pubkey, price: u64, collection: bool) -> Instruction {
+        instruction(
+            marketplace::accounts::PlaceBid {
+                bidder: self.buyer.pubkey(),
+                target,
+                bid: self.bid_address(target),
+                global: global_address(),
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::PlaceBid { price, collection },
+        )
+    }
+
+    pub fn cancel_bid_ix(&self, target: Pubkey) -> Instruction {
+        instruction(
+            marketplace::accounts::CancelBid {
+                bidder: self.buyer.pubkey(),
+                bid: self.bid_address(target),
+                global: global_address(),
+            },
+            marketplace::instruction::CancelBid {},
+        )
+    }
+
+    /// seller delivers `mint` to the buyer's bid on `target`
+    pub fn accept_bid_ix(&self, target: Pubkey) -> Instruction {
+        instruction(
+            marketplace::accounts::AcceptBid {
+                seller: self.seller.pubkey(),
+                bidder: self.buyer.pubkey(),
+                mint: self.mint,
+                seller_ata: self.seller_ata(),
+                bidder_ata: self.buyer_ata(),
+                bid: self.bid_address(target),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                metadata: metadata_address(&self.mint),
+                treasury: self.treasury,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::AcceptBid {},
+        )
+    }
+
+    pub fn create_auction_ix(
+        &self,
+        reserve_price: u64,
+        min_increment: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Instruction {
+        instruction(
+            marketplace::accounts::CreateAuction {
+                seller: self.seller.pubkey(),
+                mint: self.mint,
+                seller_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                auction: self.auction_address(),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                metadata: metadata_address(&self.mint),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                metadata_program: mpl_token_metadata::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::CreateAuction {
+                reserve_price,
+                min_increment,
+                start_time,
+                end_time,
+            },
+        )
+    }
+
+    pub fn auction_bid_ix(
+        &self,
+        bidder: Pubkey,
+        previous_bidder: Option<Pubkey>,
+        amount: u64,
+    ) -> Instruction {
+        instruction(
+            marketplace::accounts::AuctionBid {
+                bidder,
+                previous_bidder,
+                auction: self.auction_address(),
+                global: global_address(),
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::PlaceAuctionBid { amount },
+        )
+    }
+
+    /// settles the auction, `recipient` is the highest bidder or the seller when nobody bid
+    pub fn settle_auction_ix(&self, recipient: Pubkey) -> Instruction {
+        instruction(
+            marketplace::accounts::SettleAuction {
+                payer: self.admin.pubkey(),
+                seller: self.seller.pubkey(),
+                recipient,
+                mint: self.mint,
+                recipient_ata: get_associated_token_address(&recipient, &self.mint),
+                vault: self.vault_address(),
+                auction: self.auction_address(),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                treasury: self.treasury,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::SettleAuction {},
+        )
+    }
+
+    pub fn refund_auction_ix(&self, highest_bidder: Option<Pubkey>) -> Instruction {
+        instruction(
+            marketplace::accounts::RefundAuction {
+                payer: self.admin.pubkey(),
+                seller: self.seller.pubkey(),
+                highest_bidder,
+                mint: self.mint,
+                seller_ata: self.seller_ata(),
+                vault: self.vault_address(),
+                auction: self.auction_address(),
+                global: global_address(),
+                collection_config: self.collection_config_address(),
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                associated_token_program: associated_token::ID,
+            },
+            marketplace::instruction::RefundAuction {},
+        )
+    }
+
+    /// buyer sweeps the seller's listing of `mint`
+    pub fn sweep_ix(&self, max_total: u64, all_or_nothing: bool) -> Instruction {
+        let mut ix = instruction(
+            marketplace::accounts::Sweep {
+                buyer: self.buyer.pubkey(),
+                global: global_address(),
+                treasury: self.treasury,
+                referrer: None,
+                referrer_wallet: None,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            marketplace::instruction::Sweep {
+                max_total,
+                max_prices: vec![u64::MAX],
+                all_or_nothing,
+            },
+        );
+        ix.accounts.extend([
+            AccountMeta::new(self.seller.pubkey(), false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.buyer_ata(), false),
+            AccountMeta::new(self.vault_address(), false),
+            AccountMeta::new(self.listing_address(), false),
+            AccountMeta::new_readonly(self.collection_config_address(), false),
+            // listing wasn't made through a referrer, any account does
+            AccountMeta::new_readonly(system_program::ID, false),
+        ]);
+        ix
+    }
+
+    pub async fn list(&mut self, price: u64) -> std::result::Result<(), BanksClientError> {
+        let seller = self.seller.insecure_clone();
+        self.process(&[self.list_ix(price)], &[&seller]).await
+    }
+
+    pub async fn delist(&mut self) -> std::result::Result<(), BanksClientError> {
+        let seller = self.seller.insecure_clone();
+        self.process(&[self.delist_ix()], &[&seller]).await
+    }
+
+    pub async fn purchase(&mut self) -> std::result::Result<(), BanksClientError> {
+        let buyer = self.buyer.insecure_clone();
+        self.process(&[self.purchase_ix(self.treasury)], &[&buyer])
+            .await
+    }
+
+    pub async fn freeze_thaw(&mut self, freeze: bool) -> std::result::Result<(), BanksClientError> {
+        self.process(&[self.freeze_thaw_ix(freeze)], &[]).await
+    }
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: marketplace::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn global_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"global"], &marketplace::ID).0
+}
+
+pub fn action_address(id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"action", &id.to_le_bytes()], &marketplace::ID).0
+}
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0
+}
+
+/// asserts the transaction failed with `error` from the marketplace program
+pub fn assert_error<T: std::fmt::Debug>(
+    result: std::result::Result<T, BanksClientError>,
+    error: MarketplaceErrors,
+) {
+    let code = anchor_lang::error::ERROR_CODE_OFFSET + error as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {error}"),
+        other => panic!("expected {error}, got {other:?}"),
+    }
+}
+
+/// asserts the transaction failed on an anchor account constraint
+pub fn assert_constraint_error<T: std::fmt::Debug>(
+    result: std::result::Result<T, BanksClientError>,
+    error: anchor_lang::error::ErrorCode,
+) {
+    let code = error as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected {error}"),
+        other => panic!("expected {error}, got {other:?}"),
+    }
+}
+
+fn system_account(lamports: u64) -> Account {
+    Account::new(lamports, 0, &system_program::ID)
+}
+
+fn mint_account(supply: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: None.into(),
+        supply,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+
+    rent_exempt(data, spl_token::ID)
+}
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+
+    rent_exempt(data, spl_token::ID)
+}
+
+/// token metadata account of an nft verified as member of `collection`
+fn metadata_account(mint: &Pubkey, collection: &Pubkey) -> Account {
+    use mpl_token_metadata::{
+        accounts::Metadata,
+        types::{Collection, Key, TokenStandard},
+    };
+
+    let metadata = Metadata {
+        key: Key::MetadataV1,
+        update_authority: Pubkey::new_unique(),
+        mint: *mint,
+        name: "Nft".to_string(),
+        symbol: "NFT".to_string(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: Some(Collection {
+            verified: true,
+            key: *collection,
+        }),
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    };
+
+    rent_exempt(metadata.try_to_vec().unwrap(), mpl_token_metadata::ID)
+}
+
+fn rent_exempt(data: Vec<u8>, owner: Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use common::*;
+use marketplace::{error::MarketplaceErrors, AdminAction};
+
+#[tokio::test]
+async fn fee_update_waits_two_weeks() {
+    let mut env = Env::new().await;
+    let proposed_at = env.now().await;
+
+    env.process(
+        &[env.propose_action_ix(0, AdminAction::UpdateFee { fee: 20 })],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(env.global().await.fee_action, Some(0));
+    let fees = env.fee_schedule(Some(0)).await;
+    assert_eq!(fees.fee, FEE);
+    assert_eq!(fees.new_fee, Some(20));
+    assert_eq!(fees.new_fee_effective_at, Some(proposed_at + TWO_WEEKS));
+
+    env.warp(TWO_WEEKS - 60).await;
+    assert_error(
+        env.process(&[env.execute_action_ix(0)], &[]).await,
+        MarketplaceErrors::TimelockNotElapsed,
+    );
+
+    env.warp(60).await;
+    env.process(&[env.execute_action_ix(0)], &[]).await.unwrap();
+
+    let global = env.global().await;
+    assert_eq!(global.fee, 20);
+    assert_eq!(global.fee_action, None);
+    let fees = env.fee_schedule(None).await;
+    assert_eq!(fees.fee, 20);
+    assert_eq!(fees.new_fee, None);
+}
+
+#[tokio::test]
+async fn fee_update_one_at_a_time() {
+    let mut env = Env::new().await;
+
+    env.process(
+        &[env.propose_action_ix(0, AdminAction::UpdateFee { fee: 20 })],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_error(
+        env.process(
+            &[env.propose_action_ix(1, AdminAction::UpdateFee { fee: 30 })],
+            &[],
+        )
+        .await,
+        MarketplaceErrors::FeeUpdatePending,
+    );
+
+    // cancelling frees the slot, the pending fee is read from the new action
+    env.process(&[env.cancel_action_ix(0)], &[]).await.unwrap();
+    env.process(
+        &[env.propose_action_ix(1, AdminAction::UpdateFee { fee: 30 })],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.fee_schedule(Some(1)).await.new_fee, Some(30));
+}
+
+#[tokio::test]
+async fn fee_update_is_capped() {
+    let mut env = Env::new().await;
+
+    assert_error(
+        env.process(
+            &[env.propose_action_ix(0, AdminAction::UpdateFee { fee: 51 })],
+            &[],
+        )
+        .await,
+        MarketplaceErrors::MaxFee,
+    );
+}
+
+#[tokio::test]
+async fn purchase_charges_fee_snapshotted_at_listing() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    env.process(
+        &[env.propose_action_ix(0, AdminAction::UpdateFee { fee: 10 })],
+        &[],
+    )
+    .await
+    .unwrap();
+    env.warp(TWO_WEEKS).await;
+    env.process(&[env.execute_action_ix(0)], &[]).await.unwrap();
+    assert_eq!(env.global().await.fee, 10);
+
+    let treasury = env.treasury;
+    let treasury_before = env.lamports(treasury).await;
+
+    env.purchase().await.unwrap();
+
+    // listed before the update, so the old fee still applies
+    assert_eq!(
+        env.lamports(treasury).await,
+        treasury_before + PRICE * FEE as u64 / 10000
+    );
+}
+
+#[tokio::test]
+async fn listing_after_fee_update_uses_new_fee() {
+    let mut env = Env::new().await;
+
+    env.process(
+        &[env.propose_action_ix(0, AdminAction::UpdateFee { fee: 10 })],
+        &[],
+    )
+    .await
+    .unwrap();
+    env.warp(TWO_WEEKS).await;
+    env.process(&[env.execute_action_ix(0)], &[]).await.unwrap();
+
+    env.list(PRICE).await.unwrap();
+    assert_eq!(env.listing().await.unwrap().fee, 10);
+
+    let treasury = env.treasury;
+    let treasury_before = env.lamports(treasury).await;
+
+    env.purchase().await.unwrap();
+
+    assert_eq!(
+        env.lamports(treasury).await,
+        treasury_before + PRICE * 10 / 10000
+    );
+}
+
+#[tokio::test]
+async fn treasury_change_is_timelocked() {
+    let mut env = Env::new().await;
+    let treasury = solana_sdk::pubkey::Pubkey::new_unique();
+
+    env.process(
+        &[env.propose_action_ix(0, AdminAction::SetTreasury { treasury })],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    env.warp(ONE_WEEK - 1).await;
+    assert_error(
+        env.process(&[env.execute_action_ix(0)], &[]).await,
+        MarketplaceErrors::TimelockNotElapsed,
+    );
+
+    env.warp(1).await;
+    env.process(&[env.execute_action_ix(0)], &[]).await.unwrap();
+    assert_eq!(env.global().await.treasury, treasury);
+
+    // purchases must now pay the new treasury
+    env.list(PRICE).await.unwrap();
+    let old_treasury = env.treasury;
+    let buyer = env.buyer.insecure_clone();
+    assert_constraint_error(
+        env.process(&[env.purchase_ix(old_treasury)], &[&buyer])
+            .await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+}
+
+#[tokio::test]
+async fn collection_fee_override_is_timelocked() {
+    let mut env = Env::new().await;
+    let action = AdminAction::SetCollectionFee {
+        collection_mint: env.collection_mint,
+        fee: Some(10),
+    };
+
+    env.process(&[env.propose_action_ix(0, action)], &[])
+        .await
+        .unwrap();
+
+    // re-saving the config doesn't touch the fee
+    env.process(&[env.set_collection_ix(true)], &[])
+        .await
+        .unwrap();
+    assert_eq!(env.collection_config().await.fee, None);
+
+    env.warp(TWO_WEEKS - 60).await;
+    let collection_config = Some(env.collection_config_address());
+    assert_error(
+        env.process(&[env.execute_action_ix_with(0, collection_config)], &[])
+            .await,
+        MarketplaceErrors::TimelockNotElapsed,
+    );
+
+    env.warp(60).await;
+    assert_error(
+        env.process(&[env.execute_action_ix(0)], &[]).await,
+        MarketplaceErrors::InvalidActionAccount,
+    );
+    env.process(&[env.execute_action_ix_with(0, collection_config)], &[])
+        .await
+        .unwrap();
+    assert_eq!(env.collection_config().await.fee, Some(10));
+
+    env.list(PRICE).await.unwrap();
+    assert_eq!(env.listing().await.unwrap().fee, 10);
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::*;
+use marketplace::error::MarketplaceErrors;
+
+#[tokio::test]
+async fn frozen_protocol_rejects_list_and_purchase() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    env.freeze_thaw(true).await.unwrap();
+    assert!(env.global().await.frozen);
+
+    assert_error(env.purchase().await, MarketplaceErrors::ProtocolFrozen);
+
+    env.freeze_thaw(false).await.unwrap();
+    env.purchase().await.unwrap();
+}
+
+#[tokio::test]
+async fn frozen_protocol_rejects_listing() {
+    let mut env = Env::new().await;
+    env.freeze_thaw(true).await.unwrap();
+
+    assert_error(env.list(PRICE).await, MarketplaceErrors::ProtocolFrozen);
+}
+
+#[tokio::test]
+async fn freeze_requires_state_change() {
+    let mut env = Env::new().await;
+
+    assert_error(env.freeze_thaw(false).await, MarketplaceErrors::SameState);
+}
+
+#[tokio::test]
+async fn frozen_delist_waits_one_week() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+    env.freeze_thaw(true).await.unwrap();
+
+    assert_error(env.delist().await, MarketplaceErrors::FrozenDelistDelay);
+
+    env.warp(ONE_WEEK - 60).await;
+    assert_error(env.delist().await, MarketplaceErrors::FrozenDelistDelay);
+
+    env.warp(60).await;
+    env.delist().await.unwrap();
+    assert!(env.listing().await.is_none());
+}
@@ -0,0 +1,283 @@
+mod common;
+
+use anchor_lang::{error::ErrorCode, prelude::Pubkey};
+use common::*;
+use marketplace::{error::MarketplaceErrors, Custody, DutchAuction, PriceCurve};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn list_moves_nft_into_vault() {
+    let mut env = Env::new().await;
+
+    env.list(PRICE).await.unwrap();
+
+    let listing = env.listing().await.unwrap();
+    assert_eq!(listing.seller, env.seller.pubkey());
+    assert_eq!(listing.price, PRICE);
+    assert_eq!(listing.fee, FEE);
+    assert_eq!(listing.collection, env.collection_mint);
+
+    let (seller_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(seller_ata).await, Some(0));
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
+
+#[tokio::test]
+async fn delist_returns_nft_and_closes_accounts() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    env.delist().await.unwrap();
+
+    let (seller_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(seller_ata).await, Some(1));
+    assert!(env.account(vault).await.is_none());
+    assert!(env.listing().await.is_none());
+}
+
+#[tokio::test]
+async fn purchase_pays_seller_and_treasury() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    let seller = env.seller.pubkey();
+    let treasury = env.treasury;
+    let seller_before = env.lamports(seller).await;
+    let treasury_before = env.lamports(treasury).await;
+    let listing_rent = env.lamports(env.listing_address()).await;
+    let vault_rent = env.lamports(env.vault_address()).await;
+
+    env.purchase().await.unwrap();
+
+    let fee = PRICE * FEE as u64 / 10000;
+    assert_eq!(env.lamports(treasury).await, treasury_before + fee);
+    assert_eq!(
+        env.lamports(seller).await,
+        seller_before + PRICE - fee + listing_rent + vault_rent
+    );
+
+    let (buyer_ata, vault) = (env.buyer_ata(), env.vault_address());
+    assert_eq!(env.token_amount(buyer_ata).await, Some(1));
+    assert!(env.account(vault).await.is_none());
+    assert!(env.listing().await.is_none());
+}
+
+#[tokio::test]
+async fn purchase_rejects_wrong_treasury() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    let buyer = env.buyer.insecure_clone();
+    let result = env
+        .process(&[env.purchase_ix(Pubkey::new_unique())], &[&buyer])
+        .await;
+
+    assert_constraint_error(result, ErrorCode::ConstraintAddress);
+    assert!(env.listing().await.is_some());
+}
+
+#[tokio::test]
+async fn purchase_rejects_price_raised_above_max() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    let seller = env.seller.insecure_clone();
+    env.process(&[env.update_listing_ix(2 * PRICE, None)], &[&seller])
+        .await
+        .unwrap();
+    assert_eq!(env.listing().await.unwrap().price, 2 * PRICE);
+
+    let buyer = env.buyer.insecure_clone();
+    let treasury = env.treasury;
+    let result = env
+        .process(&[env.purchase_ix_with(treasury, PRICE)], &[&buyer])
+        .await;
+    assert_error(result, MarketplaceErrors::PriceAboveMax);
+
+    env.process(&[env.purchase_ix_with(treasury, 2 * PRICE)], &[&buyer])
+        .await
+        .unwrap();
+    assert!(env.listing().await.is_none());
+}
+
+#[tokio::test]
+async fn dutch_listing_price_decays() {
+    let mut env = Env::new().await;
+    let now = env.now().await;
+    let decay = DutchAuction {
+        curve: PriceCurve::Linear,
+        floor_price: PRICE / 2,
+        start_time: now,
+        end_time: now + 1000,
+    };
+    let seller = env.seller.insecure_clone();
+    env.process(&[env.list_dutch_ix(PRICE, decay)], &[&seller])
+        .await
+        .unwrap();
+
+    // halfway through the decay the price is halfway down to the floor
+    env.warp(500).await;
+    let price = PRICE * 3 / 4;
+    let treasury = env.treasury;
+    let seller_before = env.lamports(seller.pubkey()).await;
+    let listing_rent = env.lamports(env.listing_address()).await;
+    let vault_rent = env.lamports(env.vault_address()).await;
+
+    let buyer = env.buyer.insecure_clone();
+    let result = env
+        .process(&[env.purchase_ix_with(treasury, price - 1)], &[&buyer])
+        .await;
+    assert_error(result, MarketplaceErrors::PriceAboveMax);
+    env.process(&[env.purchase_ix_with(treasury, price)], &[&buyer])
+        .await
+        .unwrap();
+
+    let fee = price * FEE as u64 / 10000;
+    assert_eq!(
+        env.lamports(seller.pubkey()).await,
+        seller_before + price - fee + listing_rent + vault_rent
+    );
+}
+
+#[tokio::test]
+async fn expired_listing_is_returned_by_crank() {
+    let mut env = Env::new().await;
+    let seller = env.seller.insecure_clone();
+    let expires_at = env.now().await + 100;
+    env.process(
+        &[env.list_ix_with(PRICE, Some(expires_at), None)],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let result = env.process(&[env.expire_listing_ix()], &[]).await;
+    assert_error(result, MarketplaceErrors::ListingNotExpired);
+
+    env.warp(100).await;
+    let result = env.purchase().await;
+    assert_error(result, MarketplaceErrors::ListingExpired);
+
+    env.process(&[env.expire_listing_ix()], &[]).await.unwrap();
+
+    let (seller_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(seller_ata).await, Some(1));
+    assert!(env.account(vault).await.is_none());
+    assert!(env.listing().await.is_none());
+}
+
+#[tokio::test]
+async fn update_listing_sets_expiry() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    let seller = env.seller.insecure_clone();
+    let expires_at = env.now().await + 100;
+    env.process(
+        &[env.update_listing_ix(PRICE / 2, Some(expires_at))],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let listing = env.listing().await.unwrap();
+    assert_eq!(listing.price, PRICE / 2);
+    assert_eq!(listing.expires_at, Some(expires_at));
+
+    env.warp(100).await;
+    let result = env.purchase().await;
+    assert_error(result, MarketplaceErrors::ListingExpired);
+}
+
+#[tokio::test]
+async fn private_listing_rejects_other_buyers() {
+    let mut env = Env::new().await;
+    let seller = env.seller.insecure_clone();
+    let admin = env.admin.pubkey();
+    env.process(&[env.list_ix_with(PRICE, None, Some(admin))], &[&seller])
+        .await
+        .unwrap();
+
+    let result = env.purchase().await;
+    assert_error(result, MarketplaceErrors::BuyerNotAllowed);
+    assert!(env.listing().await.is_some());
+}
+
+#[tokio::test]
+async fn vault_paths_reject_other_custody() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    // same listing recorded as delegated, only list_delegated's own paths may unwind it
+    let mut listing = env.listing().await.unwrap();
+    listing.custody = Custody::Delegated;
+    env.set_listing(env.listing_address(), &listing);
+
+    let result = env.delist().await;
+    assert_error(result, MarketplaceErrors::CustodyNotSupported);
+    let result = env.purchase().await;
+    assert_error(result, MarketplaceErrors::CustodyNotSupported);
+    let seller = env.seller.insecure_clone();
+    let result = env.process(&[env.batch_delist_ix()], &[&seller]).await;
+    assert_error(result, MarketplaceErrors::CustodyNotSupported);
+
+    let vault = env.vault_address();
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
+
+/// records the env's vault listing under a new asset with `custody`, returns the asset
+async fn asset_listing(env: &mut Env, custody: Custody) -> Pubkey {
+    env.list(PRICE).await.unwrap();
+
+    let asset = Pubkey::new_unique();
+    let (address, bump) = env.asset_listing_address(&asset);
+    let mut listing = env.listing().await.unwrap();
+    listing.custody = custody;
+    listing.bump = bump;
+    env.set_listing(address, &listing);
+    asset
+}
+
+#[tokio::test]
+async fn update_listing_updates_core_listing() {
+    let mut env = Env::new().await;
+    let asset = asset_listing(&mut env, Custody::Core).await;
+
+    let seller = env.seller.insecure_clone();
+    env.process(
+        &[env.update_asset_listing_ix(asset, 2 * PRICE, None)],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let address = env.asset_listing_address(&asset).0;
+    assert_eq!(env.listing_at(address).await.unwrap().price, 2 * PRICE);
+}
+
+#[tokio::test]
+async fn update_listing_updates_compressed_listing() {
+    let mut env = Env::new().await;
+    let asset_id = asset_listing(&mut env, Custody::Compressed).await;
+
+    let seller = env.seller.insecure_clone();
+    env.process(
+        &[env.update_asset_listing_ix(asset_id, PRICE / 2, None)],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let address = env.asset_listing_address(&asset_id).0;
+    assert_eq!(env.listing_at(address).await.unwrap().price, PRICE / 2);
+
+    // only vault listings can be reclaimed on expiry
+    let expires_at = env.now().await + 100;
+    let result = env
+        .process(
+            &[env.update_asset_listing_ix(asset_id, PRICE, Some(expires_at))],
+            &[&seller],
+        )
+        .await;
+    assert_error(result, MarketplaceErrors::ExpiryNotSupported);
+}
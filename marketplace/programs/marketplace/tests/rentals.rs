@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+use marketplace::error::MarketplaceErrors;
+use solana_sdk::signature::Signer;
+
+const PRICE_PER_DAY: u64 = 10_000_000;
+
+#[tokio::test]
+async fn list_rental_moves_nft_into_vault() {
+    let mut env = Env::new().await;
+    let owner = env.seller.insecure_clone();
+
+    env.process(&[env.list_rental_ix(PRICE_PER_DAY, 7)], &[&owner])
+        .await
+        .unwrap();
+
+    let rental = env.rental().await.unwrap();
+    assert_eq!(rental.owner, owner.pubkey());
+    assert_eq!(rental.total_price(), 7 * PRICE_PER_DAY);
+    assert_eq!(rental.fee, FEE);
+    assert_eq!(rental.renter, None);
+
+    let (owner_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(owner_ata).await, Some(0));
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
+
+#[tokio::test]
+async fn list_rental_rejects_empty_rental() {
+    let mut env = Env::new().await;
+    let owner = env.seller.insecure_clone();
+
+    let result = env
+        .process(&[env.list_rental_ix(PRICE_PER_DAY, 0)], &[&owner])
+        .await;
+    assert_error(result, MarketplaceErrors::InvalidRental);
+
+    let result = env.process(&[env.list_rental_ix(0, 7)], &[&owner]).await;
+    assert_error(result, MarketplaceErrors::InvalidRental);
+}
+
+#[tokio::test]
+async fn delist_rental_returns_nft_and_closes_accounts() {
+    let mut env = Env::new().await;
+    let owner = env.seller.insecure_clone();
+    env.process(&[env.list_rental_ix(PRICE_PER_DAY, 7)], &[&owner])
+        .await
+        .unwrap();
+
+    env.process(&[env.delist_rental_ix()], &[&owner])
+        .await
+        .unwrap();
+
+    let (owner_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(owner_ata).await, Some(1));
+    assert!(env.account(vault).await.is_none());
+    assert!(env.rental().await.is_none());
+}
+
+#[tokio::test]
+async fn frozen_protocol_rejects_rental_listing() {
+    let mut env = Env::new().await;
+    let owner = env.seller.insecure_clone();
+    env.freeze_thaw(true).await.unwrap();
+
+    let result = env
+        .process(&[env.list_rental_ix(PRICE_PER_DAY, 7)], &[&owner])
+        .await;
+    assert_error(result, MarketplaceErrors::ProtocolFrozen);
+}
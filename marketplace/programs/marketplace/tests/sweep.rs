@@ -0,0 +1,72 @@
+mod common;
+
+use common::*;
+use marketplace::error::MarketplaceErrors;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn sweep_pays_seller_and_treasury() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    let seller = env.seller.pubkey();
+    let treasury = env.treasury;
+    let seller_before = env.lamports(seller).await;
+    let treasury_before = env.lamports(treasury).await;
+    let listing_rent = env.lamports(env.listing_address()).await;
+    let vault_rent = env.lamports(env.vault_address()).await;
+
+    let buyer = env.buyer.insecure_clone();
+    env.process(&[env.sweep_ix(PRICE, true)], &[&buyer])
+        .await
+        .unwrap();
+
+    let fee = PRICE * FEE as u64 / 10000;
+    assert_eq!(env.lamports(treasury).await, treasury_before + fee);
+    assert_eq!(
+        env.lamports(seller).await,
+        seller_before + PRICE - fee + listing_rent + vault_rent
+    );
+
+    let (buyer_ata, vault) = (env.buyer_ata(), env.vault_address());
+    assert_eq!(env.token_amount(buyer_ata).await, Some(1));
+    assert!(env.account(vault).await.is_none());
+    assert!(env.listing().await.is_none());
+}
+
+#[tokio::test]
+async fn sweep_rejects_exceeding_max_total() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    let buyer = env.buyer.insecure_clone();
+    let result = env
+        .process(&[env.sweep_ix(PRICE - 1, true)], &[&buyer])
+        .await;
+    assert_error(result, MarketplaceErrors::MaxSpendExceeded);
+}
+
+#[tokio::test]
+async fn best_effort_sweep_skips_unfillable_listings() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+
+    // the only listing is over budget, so it's skipped and nothing gets bought
+    let buyer = env.buyer.insecure_clone();
+    let result = env
+        .process(&[env.sweep_ix(PRICE - 1, false)], &[&buyer])
+        .await;
+    assert_error(result, MarketplaceErrors::NothingSwept);
+    assert!(env.listing().await.is_some());
+}
+
+#[tokio::test]
+async fn frozen_protocol_rejects_sweep() {
+    let mut env = Env::new().await;
+    env.list(PRICE).await.unwrap();
+    env.freeze_thaw(true).await.unwrap();
+
+    let buyer = env.buyer.insecure_clone();
+    let result = env.process(&[env.sweep_ix(PRICE, true)], &[&buyer]).await;
+    assert_error(result, MarketplaceErrors::ProtocolFrozen);
+}
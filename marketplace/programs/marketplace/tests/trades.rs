@@ -0,0 +1,270 @@
+mod common;
+
+use common::*;
+use marketplace::error::MarketplaceErrors;
+use solana_sdk::signature::Signer;
+
+const ID: u64 = 7;
+const LAMPORTS: u64 = PRICE / 2;
+
+#[tokio::test]
+async fn propose_trade_escrows_nft_and_sol() {
+    let mut env = Env::new().await;
+    let maker = env.seller.insecure_clone();
+
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+
+    let trade = env.trade(ID).await.unwrap();
+    assert_eq!(trade.maker, maker.pubkey());
+    assert_eq!(trade.taker, env.buyer.pubkey());
+    assert_eq!(trade.offered, vec![env.mint]);
+    assert_eq!(trade.requested, vec![env.buyer_mint]);
+    assert_eq!(trade.lamports, LAMPORTS);
+    assert_eq!(trade.collections, vec![env.collection_mint]);
+
+    let (seller_ata, vault) = (env.seller_ata(), env.vault_address());
+    assert_eq!(env.token_amount(seller_ata).await, Some(0));
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
+
+#[tokio::test]
+async fn propose_trade_rejects_fungible_mint() {
+    let mut env = Env::new().await;
+    let maker = env.seller.insecure_clone();
+    env.set_mint_supply(env.mint, 2);
+
+    let result = env
+        .process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await;
+    assert_error(result, MarketplaceErrors::NotAnNft);
+}
+
+#[tokio::test]
+async fn cancel_trade_returns_nft_and_sol() {
+    let mut env = Env::new().await;
+    let maker = env.seller.insecure_clone();
+    let maker_before = env.lamports(maker.pubkey()).await;
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+
+    env.process(&[env.cancel_trade_ix(ID, false)], &[&maker])
+        .await
+        .unwrap();
+
+    // rent of the trade and the vault is refunded, fees are paid by the admin
+    assert_eq!(env.lamports(maker.pubkey()).await, maker_before);
+    assert_eq!(env.token_amount(env.seller_ata()).await, Some(1));
+    assert!(env.account(env.vault_address()).await.is_none());
+    assert!(env.trade(ID).await.is_none());
+}
+
+#[tokio::test]
+async fn deposit_trade_escrows_requested_nft() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+
+    // nothing to swap until the taker's side is escrowed too
+    let result = env
+        .process(&[env.accept_trade_ix(ID, taker.pubkey())], &[&taker])
+        .await;
+    assert_error(result, MarketplaceErrors::TradeNotDeposited);
+
+    env.process(&[env.deposit_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+
+    assert!(env.trade(ID).await.unwrap().taker_deposited);
+    let taker_ata = env.buyer_mint_ata(&taker.pubkey());
+    assert_eq!(env.token_amount(taker_ata).await, Some(0));
+    assert_eq!(env.token_amount(env.buyer_vault_address()).await, Some(1));
+
+    let result = env.process(&[env.deposit_trade_ix(ID)], &[&taker]).await;
+    assert_error(result, MarketplaceErrors::TradeAlreadyDeposited);
+}
+
+#[tokio::test]
+async fn withdraw_trade_returns_deposit() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    let taker_before = env.lamports(taker.pubkey()).await;
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.deposit_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+
+    env.process(&[env.withdraw_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+
+    // vault rent is refunded, the trade stays open
+    assert_eq!(env.lamports(taker.pubkey()).await, taker_before);
+    let taker_ata = env.buyer_mint_ata(&taker.pubkey());
+    assert_eq!(env.token_amount(taker_ata).await, Some(1));
+    assert!(env.account(env.buyer_vault_address()).await.is_none());
+    assert!(!env.trade(ID).await.unwrap().taker_deposited);
+}
+
+#[tokio::test]
+async fn cancel_trade_returns_both_deposits() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    let maker_before = env.lamports(maker.pubkey()).await;
+    let taker_before = env.lamports(taker.pubkey()).await;
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.deposit_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+
+    // the taker's deposit has to be returned along with the maker's
+    let result = env
+        .process(&[env.cancel_trade_ix(ID, false)], &[&maker])
+        .await;
+    assert_error(result, MarketplaceErrors::InvalidTradeAccounts);
+    env.process(&[env.cancel_trade_ix(ID, true)], &[&maker])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(maker.pubkey()).await, maker_before);
+    assert_eq!(env.lamports(taker.pubkey()).await, taker_before);
+    assert_eq!(env.token_amount(env.seller_ata()).await, Some(1));
+    let taker_ata = env.buyer_mint_ata(&taker.pubkey());
+    assert_eq!(env.token_amount(taker_ata).await, Some(1));
+    assert!(env.account(env.buyer_vault_address()).await.is_none());
+    assert!(env.trade(ID).await.is_none());
+}
+
+#[tokio::test]
+async fn accept_trade_swaps_both_sides() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    let maker_before = env.lamports(maker.pubkey()).await;
+    let taker_before = env.lamports(taker.pubkey()).await;
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.deposit_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+
+    env.process(&[env.accept_trade_ix(ID, taker.pubkey())], &[&taker])
+        .await
+        .unwrap();
+
+    // both vaults' rent goes back to whoever deposited
+    assert_eq!(env.lamports(maker.pubkey()).await, maker_before - LAMPORTS);
+    assert_eq!(env.lamports(taker.pubkey()).await, taker_before + LAMPORTS);
+
+    let maker_received = env.buyer_mint_ata(&maker.pubkey());
+    let taker_sent = env.buyer_mint_ata(&taker.pubkey());
+    assert_eq!(env.token_amount(env.buyer_ata()).await, Some(1));
+    assert_eq!(env.token_amount(maker_received).await, Some(1));
+    assert_eq!(env.token_amount(taker_sent).await, Some(0));
+    assert!(env.account(env.vault_address()).await.is_none());
+    assert!(env.account(env.buyer_vault_address()).await.is_none());
+    assert!(env.trade(ID).await.is_none());
+}
+
+#[tokio::test]
+async fn propose_trade_takes_over_prefunded_vault() {
+    let mut env = Env::new().await;
+    let maker = env.seller.insecure_clone();
+
+    // dust sent to the vault address mustn't block the trade
+    let vault = env.vault_address();
+    env.fund(vault, 1);
+
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+    assert_eq!(env.token_amount(vault).await, Some(1));
+}
+
+#[tokio::test]
+async fn accept_trade_rejects_other_taker() {
+    let mut env = Env::new().await;
+    let maker = env.seller.insecure_clone();
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+
+    let admin = env.admin.pubkey();
+    let result = env.process(&[env.accept_trade_ix(ID, admin)], &[]).await;
+    assert_error(result, MarketplaceErrors::TakerNotAllowed);
+}
+
+#[tokio::test]
+async fn frozen_protocol_rejects_accepting_trade() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.deposit_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+    env.freeze_thaw(true).await.unwrap();
+
+    let result = env
+        .process(&[env.accept_trade_ix(ID, taker.pubkey())], &[&taker])
+        .await;
+    assert_error(result, MarketplaceErrors::ProtocolFrozen);
+
+    // both sides still get their nfts back after the freeze delay
+    let result = env
+        .process(&[env.cancel_trade_ix(ID, true)], &[&maker])
+        .await;
+    assert_error(result, MarketplaceErrors::FrozenDelistDelay);
+    env.warp(ONE_WEEK).await;
+    env.process(&[env.cancel_trade_ix(ID, true)], &[&maker])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn propose_trade_rejects_disabled_collection() {
+    let mut env = Env::new().await;
+    let maker = env.seller.insecure_clone();
+    env.process(&[env.set_collection_ix(false)], &[])
+        .await
+        .unwrap();
+
+    let result = env
+        .process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await;
+    assert_error(result, MarketplaceErrors::CollectionDisabled);
+}
+
+#[tokio::test]
+async fn disabled_collection_rejects_accepting_trade() {
+    let mut env = Env::new().await;
+    let (maker, taker) = (env.seller.insecure_clone(), env.buyer.insecure_clone());
+    env.process(&[env.propose_trade_ix(ID, LAMPORTS)], &[&maker])
+        .await
+        .unwrap();
+    env.process(&[env.deposit_trade_ix(ID)], &[&taker])
+        .await
+        .unwrap();
+    env.process(&[env.set_collection_ix(false)], &[])
+        .await
+        .unwrap();
+
+    let result = env
+        .process(&[env.accept_trade_ix(ID, taker.pubkey())], &[&taker])
+        .await;
+    assert_error(result, MarketplaceErrors::CollectionDisabled);
+
+    // unlike a protocol freeze, both sides can pull out right away
+    env.process(&[env.cancel_trade_ix(ID, true)], &[&maker])
+        .await
+        .unwrap();
+}